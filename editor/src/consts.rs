@@ -53,6 +53,17 @@ pub const PIVOT_DIAMETER: f64 = 5.;
 // Transformation cage
 pub const BOUNDS_SELECT_THRESHOLD: f64 = 10.;
 pub const BOUNDS_ROTATE_THRESHOLD: f64 = 20.;
+pub const BOUNDS_ROTATION_COMPASS_RADIUS: f64 = 40.;
+pub const BOUNDS_ROTATION_ARC_RADIUS: f64 = 60.;
+pub const BOUNDS_HANDLE_HALO_WIDTH: f64 = 2.;
+pub const BOUNDS_ANGLE_GUIDE_EXTENT: f64 = 10_000.;
+pub const BOUNDS_RATIO_SNAP_TOLERANCE: f64 = 0.03;
+pub const BOUNDS_GUIDE_SNAP_THRESHOLD: f64 = 5.;
+pub const BOUNDS_GUIDE_RELEASE_THRESHOLD: f64 = 12.;
+pub const BOUNDS_RADIUS_HANDLE_MIN_INSET: f64 = 16.;
+pub const BOUNDS_INERTIA_DECAY_RATE: f64 = 6.;
+pub const BOUNDS_INERTIA_STOP_SPEED: f64 = 0.05;
+pub const BOUNDS_CURSOR_HYSTERESIS: f64 = 3.;
 
 // Path tool
 pub const MANIPULATOR_GROUP_MARKER_SIZE: f64 = 6.;
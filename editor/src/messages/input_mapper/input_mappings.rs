@@ -102,6 +102,28 @@ pub fn input_mappings() -> Mapping {
 		entry!(DoubleClick(MouseButton::Left); action_dispatch=SelectToolMessage::EditLayer),
 		entry!(KeyDown(MouseRight); action_dispatch=SelectToolMessage::Abort),
 		entry!(KeyDown(Escape); action_dispatch=SelectToolMessage::Abort),
+		entry!(KeyDown(Numpad1); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 1 }),
+		entry!(KeyDown(Numpad2); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 2 }),
+		entry!(KeyDown(Numpad3); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 3 }),
+		entry!(KeyDown(Numpad4); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 4 }),
+		entry!(KeyDown(Numpad5); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 5 }),
+		entry!(KeyDown(Numpad6); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 6 }),
+		entry!(KeyDown(Numpad7); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 7 }),
+		entry!(KeyDown(Numpad8); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 8 }),
+		entry!(KeyDown(Numpad9); action_dispatch=SelectToolMessage::SetPivotAnchor { digit: 9 }),
+		entry!(KeyDown(Backspace); action_dispatch=SelectToolMessage::TypeBackspace),
+		entry!(KeyDown(Comma); action_dispatch=SelectToolMessage::TypeDecimalPoint),
+		entry!(KeyDown(Period); action_dispatch=SelectToolMessage::TypeDecimalPoint),
+		entry!(KeyDown(Digit0); action_dispatch=SelectToolMessage::TypeDigit { digit: 0 }),
+		entry!(KeyDown(Digit1); action_dispatch=SelectToolMessage::TypeDigit { digit: 1 }),
+		entry!(KeyDown(Digit2); action_dispatch=SelectToolMessage::TypeDigit { digit: 2 }),
+		entry!(KeyDown(Digit3); action_dispatch=SelectToolMessage::TypeDigit { digit: 3 }),
+		entry!(KeyDown(Digit4); action_dispatch=SelectToolMessage::TypeDigit { digit: 4 }),
+		entry!(KeyDown(Digit5); action_dispatch=SelectToolMessage::TypeDigit { digit: 5 }),
+		entry!(KeyDown(Digit6); action_dispatch=SelectToolMessage::TypeDigit { digit: 6 }),
+		entry!(KeyDown(Digit7); action_dispatch=SelectToolMessage::TypeDigit { digit: 7 }),
+		entry!(KeyDown(Digit8); action_dispatch=SelectToolMessage::TypeDigit { digit: 8 }),
+		entry!(KeyDown(Digit9); action_dispatch=SelectToolMessage::TypeDigit { digit: 9 }),
 		//
 		// ArtboardToolMessage
 		entry!(KeyDown(MouseLeft); action_dispatch=ArtboardToolMessage::PointerDown),
@@ -131,16 +131,16 @@ pub enum Key {
 	ArrowUp,
 
 	// Numpad keys
-	// Numpad0,
-	// Numpad1,
-	// Numpad2,
-	// Numpad3,
-	// Numpad4,
-	// Numpad5,
-	// Numpad6,
-	// Numpad7,
-	// Numpad8,
-	// Numpad9,
+	Numpad0,
+	Numpad1,
+	Numpad2,
+	Numpad3,
+	Numpad4,
+	Numpad5,
+	Numpad6,
+	Numpad7,
+	Numpad8,
+	Numpad9,
 	NumLock,
 	NumpadAdd,
 	// NumpadBackspace,
@@ -274,6 +274,16 @@ impl fmt::Display for Key {
 			Self::ArrowUp => "↑",
 
 			// Numpad keys
+			Self::Numpad0 => "Numpad 0",
+			Self::Numpad1 => "Numpad 1",
+			Self::Numpad2 => "Numpad 2",
+			Self::Numpad3 => "Numpad 3",
+			Self::Numpad4 => "Numpad 4",
+			Self::Numpad5 => "Numpad 5",
+			Self::Numpad6 => "Numpad 6",
+			Self::Numpad7 => "Numpad 7",
+			Self::Numpad8 => "Numpad 8",
+			Self::Numpad9 => "Numpad 9",
 			Self::NumpadAdd => "Numpad +",
 			Self::NumpadHash => "Numpad #",
 			Self::NumpadMultiply => "Numpad *",
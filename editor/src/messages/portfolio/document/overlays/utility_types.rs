@@ -45,6 +45,29 @@ impl OverlayContext {
 		self.render_context.stroke();
 	}
 
+	/// Draws the outline of a quad like `quad`, but with each corner rounded by an arc of `radius`, which is limited to half the shortest side so adjacent arcs don't overlap
+	pub fn rounded_quad(&mut self, quad: Quad, radius: f64, color_fill: Option<&str>) {
+		let corners = quad.0.map(|corner| corner.round() - DVec2::splat(0.5));
+		let shortest_side = (0..4).map(|i| corners[i].distance(corners[(i + 1) % 4])).fold(f64::INFINITY, f64::min);
+		let radius = radius.clamp(0., shortest_side / 2.);
+
+		// Start halfway along the last side so every corner is reached by an arc
+		let start = corners[3].lerp(corners[0], 0.5);
+		self.render_context.begin_path();
+		self.render_context.move_to(start.x, start.y);
+		for i in 0..4 {
+			let (corner, next) = (corners[i], corners[(i + 1) % 4]);
+			self.render_context.arc_to(corner.x, corner.y, next.x, next.y, radius).expect("Failed to draw the rounded corner");
+		}
+		self.render_context.close_path();
+		if let Some(color_fill) = color_fill {
+			self.render_context.set_fill_style_str(color_fill);
+			self.render_context.fill();
+		}
+		self.render_context.set_stroke_style_str(COLOR_OVERLAY_BLUE);
+		self.render_context.stroke();
+	}
+
 	pub fn line(&mut self, start: DVec2, end: DVec2, color: Option<&str>) {
 		self.dashed_line(start, end, color, None)
 	}
@@ -111,6 +134,56 @@ impl OverlayContext {
 		self.render_context.stroke();
 	}
 
+	/// Draws a square handle whose sides follow `axes` instead of the screen axes, so it appears as a parallelogram under a skewed transform.
+	/// Falls back to a screen-aligned square if either axis is degenerate.
+	pub fn oriented_square(&mut self, position: DVec2, size: Option<f64>, axes: [DVec2; 2], color_fill: Option<&str>, color_stroke: Option<&str>) {
+		let [Some(x_axis), Some(y_axis)] = axes.map(|axis| axis.try_normalize()) else {
+			self.square(position, size, color_fill, color_stroke);
+			return;
+		};
+
+		let size = size.unwrap_or(MANIPULATOR_GROUP_MARKER_SIZE);
+		let color_fill = color_fill.unwrap_or(COLOR_OVERLAY_WHITE);
+		let color_stroke = color_stroke.unwrap_or(COLOR_OVERLAY_BLUE);
+
+		let position = position.round() - DVec2::splat(0.5);
+		let [x, y] = [x_axis * size / 2., y_axis * size / 2.];
+		let corners = [position - x - y, position + x - y, position + x + y, position - x + y];
+
+		self.render_context.begin_path();
+		self.render_context.move_to(corners[0].x, corners[0].y);
+		for corner in &corners[1..] {
+			self.render_context.line_to(corner.x, corner.y);
+		}
+		self.render_context.close_path();
+		self.render_context.set_fill_style_str(color_fill);
+		self.render_context.set_stroke_style_str(color_stroke);
+		self.render_context.fill();
+		self.render_context.stroke();
+	}
+
+	/// Draws a square handle rotated by 45°, so its corners point along the screen axes, with the same bounding size as `square`
+	pub fn diamond(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, color_stroke: Option<&str>) {
+		let size = size.unwrap_or(MANIPULATOR_GROUP_MARKER_SIZE);
+		let color_fill = color_fill.unwrap_or(COLOR_OVERLAY_WHITE);
+		let color_stroke = color_stroke.unwrap_or(COLOR_OVERLAY_BLUE);
+
+		let position = position.round() - DVec2::splat(0.5);
+		let [x, y] = [DVec2::X * size / 2., DVec2::Y * size / 2.];
+		let corners = [position - y, position + x, position + y, position - x];
+
+		self.render_context.begin_path();
+		self.render_context.move_to(corners[0].x, corners[0].y);
+		for corner in &corners[1..] {
+			self.render_context.line_to(corner.x, corner.y);
+		}
+		self.render_context.close_path();
+		self.render_context.set_fill_style_str(color_fill);
+		self.render_context.set_stroke_style_str(color_stroke);
+		self.render_context.fill();
+		self.render_context.stroke();
+	}
+
 	pub fn pixel(&mut self, position: DVec2, color: Option<&str>) {
 		let size = 1.;
 		let color_fill = color.unwrap_or(COLOR_OVERLAY_WHITE);
@@ -280,6 +353,49 @@ impl OverlayContext {
 	}
 }
 
+/// The drawing operations used to draw overlays, so that code drawing them can be given a recording implementation in tests rather than the `OverlayContext` which draws to the canvas
+pub trait OverlayDraw {
+	fn quad(&mut self, quad: Quad, color_fill: Option<&str>);
+	fn rounded_quad(&mut self, quad: Quad, radius: f64, color_fill: Option<&str>);
+	fn dashed_line(&mut self, start: DVec2, end: DVec2, color: Option<&str>, dash_width: Option<f64>);
+	fn square(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, color_stroke: Option<&str>);
+	fn oriented_square(&mut self, position: DVec2, size: Option<f64>, axes: [DVec2; 2], color_fill: Option<&str>, color_stroke: Option<&str>);
+	fn diamond(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, color_stroke: Option<&str>);
+	fn circle(&mut self, position: DVec2, radius: f64, color_fill: Option<&str>, color_stroke: Option<&str>);
+	fn text(&mut self, text: &str, font_color: &str, background_color: Option<&str>, transform: DAffine2, padding: f64, pivot: [Pivot; 2]);
+
+	fn line(&mut self, start: DVec2, end: DVec2, color: Option<&str>) {
+		self.dashed_line(start, end, color, None)
+	}
+}
+
+impl OverlayDraw for OverlayContext {
+	fn quad(&mut self, quad: Quad, color_fill: Option<&str>) {
+		OverlayContext::quad(self, quad, color_fill)
+	}
+	fn rounded_quad(&mut self, quad: Quad, radius: f64, color_fill: Option<&str>) {
+		OverlayContext::rounded_quad(self, quad, radius, color_fill)
+	}
+	fn dashed_line(&mut self, start: DVec2, end: DVec2, color: Option<&str>, dash_width: Option<f64>) {
+		OverlayContext::dashed_line(self, start, end, color, dash_width)
+	}
+	fn square(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, color_stroke: Option<&str>) {
+		OverlayContext::square(self, position, size, color_fill, color_stroke)
+	}
+	fn oriented_square(&mut self, position: DVec2, size: Option<f64>, axes: [DVec2; 2], color_fill: Option<&str>, color_stroke: Option<&str>) {
+		OverlayContext::oriented_square(self, position, size, axes, color_fill, color_stroke)
+	}
+	fn diamond(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, color_stroke: Option<&str>) {
+		OverlayContext::diamond(self, position, size, color_fill, color_stroke)
+	}
+	fn circle(&mut self, position: DVec2, radius: f64, color_fill: Option<&str>, color_stroke: Option<&str>) {
+		OverlayContext::circle(self, position, radius, color_fill, color_stroke)
+	}
+	fn text(&mut self, text: &str, font_color: &str, background_color: Option<&str>, transform: DAffine2, padding: f64, pivot: [Pivot; 2]) {
+		OverlayContext::text(self, text, font_color, background_color, transform, padding, pivot)
+	}
+}
+
 pub enum Pivot {
 	Start,
 	Middle,
@@ -237,6 +237,10 @@ impl SnapManager {
 	pub fn update_indicator(&mut self, snapped_point: SnappedPoint) {
 		self.indicator = snapped_point.is_snapped().then_some(snapped_point);
 	}
+	/// The snap which was most recently found, if any
+	pub fn indicator(&self) -> Option<&SnappedPoint> {
+		self.indicator.as_ref()
+	}
 	pub fn clear_indicator(&mut self) {
 		self.indicator = None;
 	}
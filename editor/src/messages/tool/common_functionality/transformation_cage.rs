@@ -1,13 +1,19 @@
-use crate::consts::{BOUNDS_ROTATE_THRESHOLD, BOUNDS_SELECT_THRESHOLD, SELECTION_DRAG_ANGLE};
+use crate::consts::{
+	BOUNDS_ANGLE_GUIDE_EXTENT, BOUNDS_CURSOR_HYSTERESIS, BOUNDS_GUIDE_RELEASE_THRESHOLD, BOUNDS_GUIDE_SNAP_THRESHOLD, BOUNDS_HANDLE_HALO_WIDTH, BOUNDS_INERTIA_DECAY_RATE, BOUNDS_INERTIA_STOP_SPEED,
+	BOUNDS_RADIUS_HANDLE_MIN_INSET, BOUNDS_RATIO_SNAP_TOLERANCE, BOUNDS_ROTATE_THRESHOLD, BOUNDS_ROTATION_ARC_RADIUS, BOUNDS_ROTATION_COMPASS_RADIUS, BOUNDS_SELECT_THRESHOLD, COLOR_OVERLAY_BLUE,
+	COLOR_OVERLAY_RED, COLOR_OVERLAY_SNAP_BACKGROUND, COLOR_OVERLAY_WHITE, COLOR_OVERLAY_YELLOW, DRAG_THRESHOLD, ROTATE_SNAP_ANGLE, SELECTION_DRAG_ANGLE, SNAP_POINT_TOLERANCE,
+};
 use crate::messages::frontend::utility_types::MouseCursorIcon;
-use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::layout::utility_types::widgets::input_widgets::PivotPosition;
+use crate::messages::portfolio::document::overlays::utility_types::{OverlayDraw, Pivot};
 use crate::messages::portfolio::document::utility_types::transformation::OriginalTransforms;
 use crate::messages::prelude::*;
 
-use graphene_core::renderer::Quad;
+use graphene_core::renderer::{format_transform_matrix, Quad};
 
 use glam::{DAffine2, DVec2};
 use graphene_std::renderer::Rect;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
 
 use super::snapping::{self, SnapCandidatePoint, SnapConstraint, SnapData, SnapManager, SnappedPoint};
 
@@ -17,8 +23,106 @@ pub struct SizeSnapData<'a> {
 	pub snap_data: SnapData<'a>,
 }
 
+/// The edges of the bounding box that are under the cursor, with corners selecting two adjacent edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EdgeSelection {
+	pub top: bool,
+	pub bottom: bool,
+	pub left: bool,
+	pub right: bool,
+}
+
+impl EdgeSelection {
+	/// A corner selects one vertical and one horizontal edge
+	pub fn is_corner(&self) -> bool {
+		(self.top || self.bottom) && (self.left || self.right)
+	}
+
+	/// A single edge selects only the vertical or only the horizontal edges
+	pub fn is_edge(&self) -> bool {
+		(self.top || self.bottom) != (self.left || self.right)
+	}
+
+	/// The index of the handle on the selected corner or in the middle of the selected edge, in the same order as `evaluate_transform_handle_positions`
+	pub fn handle(&self) -> Option<usize> {
+		let column = if self.left {
+			0
+		} else if self.right {
+			2
+		} else {
+			1
+		};
+		let row = if self.top {
+			0
+		} else if self.bottom {
+			2
+		} else {
+			1
+		};
+		handle_index(column, row)
+	}
+}
+
+impl From<(bool, bool, bool, bool)> for EdgeSelection {
+	fn from((top, bottom, left, right): (bool, bool, bool, bool)) -> Self {
+		Self { top, bottom, left, right }
+	}
+}
+
+impl From<EdgeSelection> for (bool, bool, bool, bool) {
+	fn from(edges: EdgeSelection) -> Self {
+		(edges.top, edges.bottom, edges.left, edges.right)
+	}
+}
+
+/// A dimension of the bounds which can be typed in while resizing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResizeDimension {
+	Width,
+	Height,
+}
+
+/// Describes a snap which engaged while resizing or rotating, so tools can give feedback such as a flash or a sound
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapEvent {
+	/// The dragged edges snapped to this intersection of the resize grid, in the local space of the bounds
+	Grid(DVec2),
+	/// The rotation snapped to this angle in radians
+	Angle(f64),
+	/// A point of the selection snapped to this document space point of another object
+	Object(DVec2),
+}
+
+/// A grid in the local space of the bounds which the dragged edges snap to while resizing
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResizeGrid {
+	/// A point the grid lines pass through
+	pub origin: DVec2,
+	/// The spacing between grid lines
+	pub step: f64,
+}
+
+impl Default for ResizeGrid {
+	fn default() -> Self {
+		Self { origin: DVec2::ZERO, step: 1. }
+	}
+}
+
+impl ResizeGrid {
+	/// Rounds the point to the closest grid intersection, leaving it unchanged if the step is not positive
+	pub fn snap(&self, point: DVec2) -> DVec2 {
+		if !self.step.is_finite() || self.step <= 0. {
+			return point;
+		}
+		self.origin + ((point - self.origin) / self.step).round() * self.step
+	}
+}
+
 /// Contains the edges that are being dragged along with the original bounds.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectedEdges {
 	bounds: [DVec2; 2],
 	top: bool,
@@ -27,6 +131,32 @@ pub struct SelectedEdges {
 	right: bool,
 	// Aspect ratio in the form of width/height, so x:1 = width:height
 	aspect_ratio: f64,
+	// Point of the original bounds that stays fixed instead of the edge opposite to the one dragged
+	anchor: PivotPosition,
+	// Grid the dragged edges snap to
+	grid: Option<ResizeGrid>,
+	// Whether a dragged corner follows the diagonal through the pivot rather than the mouse itself
+	lock_to_diagonal: bool,
+	// Whether dragging an edge adjusts the perpendicular axis to keep the original area
+	fixed_area: bool,
+	// Width/height ratios which a dragged corner locks to when the size comes close to one of them
+	snap_ratios: Vec<f64>,
+	// Positions of the vertical and horizontal guide lines in the local space of the bounds, which the dragged edges stick to
+	guides: [Vec<f64>; 2],
+	// The guide each axis is currently stuck to
+	stuck_guides: [Option<f64>; 2],
+	// Bounds of neighboring objects in the local space of the bounds, which dragged edges stick to positions with equal spacing between
+	spacing_neighbors: Vec<[DVec2; 2]>,
+	// The two equal gaps along each axis while the dragged edge is stuck to an equal spacing position, as the start and end of each gap
+	spacing_gaps: [Option<[[f64; 2]; 2]>; 2],
+	// Integer width:height ratios which the size always snaps to the nearest of, while the mode is active
+	integer_ratios: Option<Vec<(u32, u32)>>,
+	// What the children of the selection should apply the resize transform to
+	resize_application: ResizeApplication,
+	// Whether dragging a single edge moves the opposite edge by the same amount the other way, keeping the center of the original bounds on that axis
+	symmetric: bool,
+	// Position along the dragged axis of a reference line in the local space of the bounds, and the gap the opposite edge keeps to it
+	reference_gap: Option<(f64, f64)>,
 }
 
 impl SelectedEdges {
@@ -40,7 +170,282 @@ impl SelectedEdges {
 			right,
 			bounds,
 			aspect_ratio,
+			anchor: PivotPosition::None,
+			grid: None,
+			lock_to_diagonal: false,
+			fixed_area: false,
+			snap_ratios: Vec::new(),
+			guides: [Vec::new(), Vec::new()],
+			stuck_guides: [None, None],
+			spacing_neighbors: Vec::new(),
+			spacing_gaps: [None, None],
+			integer_ratios: None,
+			resize_application: ResizeApplication::Full,
+			symmetric: false,
+			reference_gap: None,
+		}
+	}
+
+	pub fn from_edge_selection(edges: EdgeSelection, bounds: [DVec2; 2]) -> Self {
+		let EdgeSelection { top, bottom, left, right } = edges;
+		Self::new(top, bottom, left, right, bounds)
+	}
+
+	/// Selects all four edges so the whole frame expands or contracts uniformly about its center while dragging
+	pub fn all_edges(bounds: [DVec2; 2]) -> Self {
+		Self::new(true, true, true, true, bounds)
+	}
+
+	/// Whether all four edges are selected, see `all_edges`
+	pub fn is_all_edges(&self) -> bool {
+		self.top && self.bottom && self.left && self.right
+	}
+
+	/// Keep the named anchor of the original bounds fixed while resizing, rather than the opposite edge. `PivotPosition::None` restores the default behavior.
+	pub fn set_anchor(&mut self, anchor: PivotPosition) {
+		self.anchor = anchor;
+	}
+
+	/// Lock the aspect ratio used when constraining to an explicit width/height ratio, such as `1.` to resize as a square whatever the starting shape.
+	/// Ratios which aren't finite and positive are ignored, keeping the ratio of the original bounds.
+	pub fn set_aspect_ratio(&mut self, ratio: f64) {
+		if ratio.is_finite() && ratio > 0. {
+			self.aspect_ratio = ratio;
+		}
+	}
+
+	/// Snap the dragged edges to a grid in the local space of the bounds, or disable grid snapping with `None`
+	pub fn set_grid(&mut self, grid: Option<ResizeGrid>) {
+		self.grid = grid;
+	}
+
+	/// Mirror a dragged edge onto the opposite edge, so both move apart or together about the center of the original bounds on that axis.
+	/// Unlike the `center_around` of `new_size`, this only applies to dragging a single edge and leaves corner drags unchanged.
+	pub fn set_symmetric(&mut self, symmetric: bool) {
+		self.symmetric = symmetric;
+	}
+
+	/// While dragging a single edge, place the opposite edge `gap` away from a reference line parallel to it, on the side of the line the bounds started on, instead of keeping it where it was.
+	/// The line is given by its position along the dragged axis in the local space of the bounds, so an x coordinate when dragging the left or right edge. `None` disables the constraint.
+	pub fn set_reference_gap(&mut self, reference_gap: Option<(f64, f64)>) {
+		self.reference_gap = reference_gap;
+	}
+
+	/// Lock a dragged corner to the diagonal between it and the pivot, so the resize stays exactly proportional even as the mouse drifts
+	pub fn set_lock_to_diagonal(&mut self, lock_to_diagonal: bool) {
+		self.lock_to_diagonal = lock_to_diagonal;
+	}
+
+	/// Keep the area of the bounds constant while dragging an edge, so widening shrinks the height and vice versa. Has no effect on zero area bounds or corner drags.
+	pub fn set_fixed_area(&mut self, fixed_area: bool) {
+		self.fixed_area = fixed_area;
+	}
+
+	/// Lock the size to the nearest of these width/height ratios, such as `16. / 9.` or the golden ratio, while dragging a corner to a size within `BOUNDS_RATIO_SNAP_TOLERANCE` of it.
+	/// Portrait ratios must be listed separately, since a ratio only matches sizes in the same orientation. An empty list disables ratio snapping.
+	pub fn set_snap_ratios(&mut self, snap_ratios: Vec<f64>) {
+		self.snap_ratios = snap_ratios;
+	}
+
+	/// The ratio from `set_snap_ratios` which a corner drag to this size locks to, if any
+	pub fn snapped_ratio(&self, size: DVec2) -> Option<f64> {
+		let ratio = (size.x / size.y).abs();
+		if !self.edges().is_corner() || !ratio.is_finite() || ratio == 0. {
+			return None;
+		}
+
+		let relative_error = |candidate: f64| (ratio / candidate - 1.).abs();
+		self.snap_ratios
+			.iter()
+			.copied()
+			.filter(|&candidate| candidate > 0. && relative_error(candidate) < BOUNDS_RATIO_SNAP_TOLERANCE)
+			.min_by(|&a, &b| relative_error(a).total_cmp(&relative_error(b)))
+	}
+
+	/// While set, such as when a modifier is held, snap the size to the nearest of these integer width:height ratios, such as `(2, 1)`, however far it is from it.
+	/// Unlike `set_snap_ratios`, this always locks to a ratio and also applies to dragging a single edge. The dragged axis is kept, or the dominant one when dragging a corner.
+	pub fn set_integer_ratios(&mut self, integer_ratios: Option<Vec<(u32, u32)>>) {
+		self.integer_ratios = integer_ratios;
+	}
+
+	/// The ratio which a drag to this size locks to, either the nearest from `set_integer_ratios` when active or one from `set_snap_ratios`
+	pub fn locked_ratio(&self, size: DVec2) -> Option<f64> {
+		self.integer_ratio(size).or_else(|| self.snapped_ratio(size))
+	}
+
+	/// The nearest of the `integer_ratios` to this size, comparing ratios logarithmically so 1:2 and 2:1 are equally far from 1:1
+	fn integer_ratio(&self, size: DVec2) -> Option<f64> {
+		let ratio = (size.x / size.y).abs();
+		if self.is_all_edges() || !ratio.is_finite() || ratio == 0. {
+			return None;
+		}
+
+		let error = |candidate: f64| (ratio / candidate).ln().abs();
+		self.integer_ratios
+			.as_ref()?
+			.iter()
+			.filter(|&&(width, height)| width > 0 && height > 0)
+			.map(|&(width, height)| width as f64 / height as f64)
+			.min_by(|&a, &b| error(a).total_cmp(&error(b)))
+	}
+
+	/// Sets the x positions of vertical guide lines and the y positions of horizontal guide lines, in the local space of the bounds, for `stick_to_guides`
+	pub fn set_guides(&mut self, vertical: Vec<f64>, horizontal: Vec<f64>) {
+		self.guides = [vertical, horizontal];
+		self.stuck_guides = [None, None];
+	}
+
+	/// Sets the bounds of neighboring objects, given by their minimum and maximum corners in the local space of the bounds, for `stick_to_guides` to also stick a dragged edge
+	/// where the gap it leaves to a neighbor equals another gap along the same row or column, see `equal_spacing_gaps`
+	pub fn set_spacing_neighbors(&mut self, neighbors: Vec<[DVec2; 2]>) {
+		self.spacing_neighbors = neighbors;
+		self.stuck_guides = [None, None];
+		self.spacing_gaps = [None, None];
+	}
+
+	/// The two equal gaps, as their start and end positions in the local space of the bounds, along the x and y axes while a dragged edge is stuck to an equal spacing position
+	pub fn equal_spacing_gaps(&self) -> [Option<[[f64; 2]; 2]>; 2] {
+		self.spacing_gaps
+	}
+
+	/// The positions along an axis which the single dragged edge on it can move to for equal spacing with the `spacing_neighbors` overlapping the bounds on the other axis, along with the two equal gaps.
+	/// Either the gap to the next neighbor matches the gap on the other side of the bounds, or the gap to the next neighbor matches the gap between it and the one after.
+	fn equal_spacing(&self, axis: usize) -> Vec<(f64, [[f64; 2]; 2])> {
+		let (dragged_min, dragged_max) = if axis == 0 { (self.left, self.right) } else { (self.top, self.bottom) };
+		if dragged_min == dragged_max || self.spacing_neighbors.is_empty() {
+			return Vec::new();
+		}
+
+		let (min, max) = (self.bounds[0].min(self.bounds[1]), self.bounds[0].max(self.bounds[1]));
+		let other = 1 - axis;
+		// Mirror the axis when dragging the minimum edge, so the dragged edge always moves towards the positive side of the fixed edge
+		let sign = if dragged_max { 1. } else { -1. };
+		let mirror = |[start, end]: [f64; 2]| if dragged_max { [start, end] } else { [-end, -start] };
+		let fixed = if dragged_max { min[axis] } else { -max[axis] };
+		let neighbors = self
+			.spacing_neighbors
+			.iter()
+			.map(|&[a, b]| (a.min(b), a.max(b)))
+			.filter(|&(neighbor_min, neighbor_max)| neighbor_min[other] < max[other] && neighbor_max[other] > min[other])
+			.map(|(neighbor_min, neighbor_max)| mirror([neighbor_min[axis], neighbor_max[axis]]))
+			.collect::<Vec<_>>();
+		let ahead = neighbors.iter().filter(|&&[start, _]| start > fixed);
+
+		let mut positions = Vec::new();
+		if let Some(behind) = neighbors.iter().filter(|&&[_, end]| end <= fixed).map(|&[_, end]| end).reduce(f64::max) {
+			let gap = fixed - behind;
+			for &[start, _] in ahead.clone() {
+				positions.push((start - gap, [[behind, fixed], [start - gap, start]]));
+			}
+		}
+		for &[start, end] in ahead.clone() {
+			for &[next_start, _] in ahead.clone().filter(|&&[next_start, _]| next_start > end) {
+				let gap = next_start - end;
+				positions.push((start - gap, [[start - gap, start], [end, next_start]]));
+			}
+		}
+
+		positions
+			.into_iter()
+			.filter(|&(position, _)| position > fixed)
+			.map(|(position, gaps)| (position * sign, gaps.map(mirror)))
+			.collect()
+	}
+
+	/// Moves the viewport space mouse onto a guide line for the axes of the dragged edges, to be applied before `new_size`.
+	/// An edge sticks to a guide once within `BOUNDS_GUIDE_SNAP_THRESHOLD` of it, and then stays stuck until the mouse moves `BOUNDS_GUIDE_RELEASE_THRESHOLD` away, which prevents jitter near the guide.
+	/// Both thresholds are in viewport space. The positions giving equal spacing to the `spacing_neighbors` act as guides too.
+	pub fn stick_to_guides(&mut self, mouse: DVec2, transform: DAffine2) -> DVec2 {
+		if self.guides.iter().all(|guides| guides.is_empty()) && self.spacing_neighbors.is_empty() {
+			return mouse;
+		}
+
+		let mut local = self.project_mouse_local(mouse, transform);
+		let dragged = [self.left || self.right, self.top || self.bottom];
+		for axis in 0..2 {
+			let spacing = self.equal_spacing(axis);
+			let guides = self.guides[axis].iter().copied().chain(spacing.iter().map(|&(position, _)| position)).collect::<Vec<_>>();
+			self.spacing_gaps[axis] = None;
+			if !dragged[axis] || guides.is_empty() {
+				self.stuck_guides[axis] = None;
+				continue;
+			}
+
+			let viewport_axis = transform.transform_vector2(if axis == 0 { DVec2::X } else { DVec2::Y }).length();
+			let [snap_threshold, release_threshold] = [BOUNDS_GUIDE_SNAP_THRESHOLD, BOUNDS_GUIDE_RELEASE_THRESHOLD].map(|threshold| threshold / viewport_axis);
+
+			let stuck = self.stuck_guides[axis].filter(|&guide| (local[axis] - guide).abs() < release_threshold);
+			let nearest = || {
+				guides
+					.iter()
+					.copied()
+					.filter(|guide| (local[axis] - guide).abs() < snap_threshold)
+					.min_by(|a, b| (local[axis] - a).abs().total_cmp(&(local[axis] - b).abs()))
+			};
+			self.stuck_guides[axis] = stuck.or_else(nearest);
+			if let Some(guide) = self.stuck_guides[axis] {
+				local[axis] = guide;
+				self.spacing_gaps[axis] = spacing.iter().find(|&&(position, _)| position == guide).map(|&(_, gaps)| gaps);
+			}
+		}
+
+		transform.transform_point2(local)
+	}
+
+	/// Projects the local space mouse onto the diagonal through the pivot and the dragged corner, if that lock is enabled for a corner drag
+	fn project_onto_diagonal(&self, mouse: DVec2) -> DVec2 {
+		let is_corner = (self.top ^ self.bottom) && (self.left ^ self.right);
+		if !self.lock_to_diagonal || !is_corner {
+			return mouse;
+		}
+
+		let pivot = self.calculate_pivot();
+		let corner = DVec2::new(if self.left { self.bounds[0].x } else { self.bounds[1].x }, if self.top { self.bounds[0].y } else { self.bounds[1].y });
+		// A zero length diagonal has no direction to follow
+		let Some(direction) = (corner - pivot).try_normalize() else { return mouse };
+		pivot + direction * (mouse - pivot).dot(direction)
+	}
+
+	/// The grid snap which `new_size` applies for this mouse position, if grid snapping is enabled
+	pub fn grid_snap_event(&self, mouse: DVec2, transform: DAffine2) -> Option<SnapEvent> {
+		let grid = self.grid?;
+		Some(SnapEvent::Grid(grid.snap(self.project_mouse_local(mouse, transform))))
+	}
+
+	/// The position of the anchor override in the local space of the bounds, if one is set
+	fn anchor_position(&self) -> Option<DVec2> {
+		let normalized: Option<DVec2> = self.anchor.into();
+		normalized.map(|normalized| self.bounds[0] + (self.bounds[1] - self.bounds[0]) * normalized)
+	}
+
+	/// The index, in the order of `BoundingBoxManager::evaluate_transform_handle_positions`, of the handle being dragged, or `None` when all edges are selected
+	pub fn grabbed_handle(&self) -> Option<usize> {
+		if self.is_all_edges() {
+			return None;
+		}
+		self.edges().handle()
+	}
+
+	/// The selected edges, as returned by `BoundingBoxManager::check_selected_edges`
+	fn edges(&self) -> EdgeSelection {
+		EdgeSelection::from((self.top, self.bottom, self.left, self.right))
+	}
+
+	/// The index of the handle at the point which stays fixed during the resize, which is the anchor if one is set and otherwise the handle opposite to the dragged one.
+	/// `None` if that point isn't on a handle, such as when resizing about the center.
+	pub fn opposite_handle(&self) -> Option<usize> {
+		let fixed = self.anchor_position().unwrap_or_else(|| self.calculate_pivot());
+		let normalized = (fixed - self.bounds[0]) / (self.bounds[1] - self.bounds[0]);
+		if !normalized.is_finite() {
+			return None;
 		}
+		let [column, row] = (normalized * 2.).round().to_array().map(|position| position as usize);
+		handle_index(column, row)
+	}
+
+	/// The center of the original bounds in their local space, which is what `new_size` expects (once transformed to the viewport) as `center_around` for symmetric scaling
+	pub fn default_center_around(&self) -> DVec2 {
+		(self.bounds[0] + self.bounds[1]) / 2.
 	}
 
 	/// Calculate the pivot for the operation (the opposite point to the edge dragged)
@@ -49,7 +454,9 @@ impl SelectedEdges {
 	}
 
 	fn pivot_from_bounds(&self, min: DVec2, max: DVec2) -> DVec2 {
-		let x = if self.left {
+		let x = if self.left && self.right {
+			(min.x + max.x) / 2.
+		} else if self.left {
 			max.x
 		} else if self.right {
 			min.x
@@ -57,7 +464,9 @@ impl SelectedEdges {
 			(min.x + max.x) / 2.
 		};
 
-		let y = if self.top {
+		let y = if self.top && self.bottom {
+			(min.y + max.y) / 2.
+		} else if self.top {
 			max.y
 		} else if self.bottom {
 			min.y
@@ -68,9 +477,29 @@ impl SelectedEdges {
 		DVec2::new(x, y)
 	}
 
+	/// Maps the viewport space mouse into the local space of the bounds given the transform of the cage, as `new_size` does before resizing.
+	/// Useful for drawing a marker where the cursor lands in the frame of a rotated or skewed cage.
+	pub fn project_mouse_local(&self, mouse: DVec2, transform: DAffine2) -> DVec2 {
+		transform.inverse().transform_point2(mouse)
+	}
+
 	/// Computes the new bounds with the given mouse move and modifier keys
 	pub fn new_size(&self, mouse: DVec2, transform: DAffine2, center_around: Option<DVec2>, constrain: bool, snap: Option<SizeSnapData>) -> (DVec2, DVec2) {
-		let mouse = transform.inverse().transform_point2(mouse);
+		let (position, size, _) = self.new_size_with_unconstrained(mouse, transform, center_around, constrain, snap);
+		(position, size)
+	}
+
+	/// Computes the same position and size as `new_size`, along with the size the bounds would have had before keeping the aspect ratio or locking to a ratio,
+	/// such as for showing how far the constrained size is from following the mouse
+	pub fn new_size_with_unconstrained(&self, mouse: DVec2, transform: DAffine2, center_around: Option<DVec2>, constrain: bool, snap: Option<SizeSnapData>) -> (DVec2, DVec2, DVec2) {
+		let mouse = self.project_mouse_local(mouse, transform);
+		let mouse = self.grid.map_or(mouse, |grid| grid.snap(mouse));
+		let mouse = self.project_onto_diagonal(mouse);
+
+		if self.is_all_edges() {
+			let (position, size) = self.expand_all_edges(mouse);
+			return (position, size, size);
+		}
 
 		let mut min = self.bounds[0];
 		let mut max = self.bounds[1];
@@ -102,15 +531,58 @@ impl SelectedEdges {
 				pivot.x = center_around.x;
 				min.x = center_around.x * 2. - max.x;
 			}
+		} else if self.symmetric && (self.top || self.bottom) != (self.left || self.right) {
+			let center = (self.bounds[0] + self.bounds[1]) / 2.;
+			if self.top {
+				pivot.y = center.y;
+				max.y = center.y * 2. - min.y;
+			} else if self.bottom {
+				pivot.y = center.y;
+				min.y = center.y * 2. - max.y;
+			} else if self.left {
+				pivot.x = center.x;
+				max.x = center.x * 2. - min.x;
+			} else if self.right {
+				pivot.x = center.x;
+				min.x = center.x * 2. - max.x;
+			}
+		} else if let Some((line, gap)) = self.reference_gap.filter(|_| (self.top || self.bottom) != (self.left || self.right)) {
+			let axis = if self.top || self.bottom { 1 } else { 0 };
+			let (dragged_min, opposite) = if self.top || self.left { (true, &mut max[axis]) } else { (false, &mut min[axis]) };
+			let original = if dragged_min { self.bounds[1][axis] } else { self.bounds[0][axis] };
+			let side = if original > line || (original == line && !dragged_min) { 1. } else { -1. };
+			*opposite = line + gap.abs() * side;
+			pivot[axis] = *opposite;
+		} else if let Some(anchor) = self.anchor_position() {
+			// Scale the opposite edge about the anchor by the same factor the dragged edge moved by
+			if self.top || self.bottom {
+				let (dragged, opposite) = if self.top { (&mut min.y, &mut max.y) } else { (&mut max.y, &mut min.y) };
+				let original = if self.top { self.bounds } else { [self.bounds[1], self.bounds[0]] };
+				if let Some(scaled) = scale_about_anchor(anchor.y, original[0].y, *dragged, original[1].y) {
+					pivot.y = anchor.y;
+					*opposite = scaled;
+				}
+			}
+			if self.left || self.right {
+				let (dragged, opposite) = if self.left { (&mut min.x, &mut max.x) } else { (&mut max.x, &mut min.x) };
+				let original = if self.left { self.bounds } else { [self.bounds[1], self.bounds[0]] };
+				if let Some(scaled) = scale_about_anchor(anchor.x, original[0].x, *dragged, original[1].x) {
+					pivot.x = anchor.x;
+					*opposite = scaled;
+				}
+			}
 		}
 
-		if constrain {
+		let unconstrained_size = max - min;
+		let locked_ratio = if constrain { None } else { self.locked_ratio(max - min) };
+		if constrain || locked_ratio.is_some() {
+			let aspect_ratio = locked_ratio.unwrap_or(self.aspect_ratio);
 			let size = max - min;
 			let min_pivot = (pivot - min) / size;
 			let new_size = match ((self.top || self.bottom), (self.left || self.right)) {
-				(true, true) => DVec2::new(size.x, size.x / self.aspect_ratio).abs().max(DVec2::new(size.y * self.aspect_ratio, size.y).abs()) * size.signum(),
-				(true, false) => DVec2::new(size.y * self.aspect_ratio, size.y),
-				(false, true) => DVec2::new(size.x, size.x / self.aspect_ratio),
+				(true, true) => DVec2::new(size.x, size.x / aspect_ratio).abs().max(DVec2::new(size.y * aspect_ratio, size.y).abs()) * size.signum(),
+				(true, false) => DVec2::new(size.y * aspect_ratio, size.y),
+				(false, true) => DVec2::new(size.x, size.x / aspect_ratio),
 				_ => size,
 			};
 			let delta_size = new_size - size;
@@ -118,6 +590,22 @@ impl SelectedEdges {
 			max = min + new_size;
 		}
 
+		let original_size = self.bounds[1] - self.bounds[0];
+		let area = (original_size.x * original_size.y).abs();
+		let (vertical, horizontal) = (self.top || self.bottom, self.left || self.right);
+		if self.fixed_area && area > 0. && vertical != horizontal {
+			// The perpendicular axis is scaled about the pivot, which is its center unless an anchor is set
+			let size = max - min;
+			let axis = if vertical { 0 } else { 1 };
+			let dragged = if vertical { size.y } else { size.x };
+			if dragged != 0. {
+				let perpendicular = area / dragged.abs() * size[axis].signum();
+				let pivot_fraction = (pivot[axis] - min[axis]) / size[axis];
+				min[axis] -= (perpendicular - size[axis]) * pivot_fraction;
+				max[axis] = min[axis] + perpendicular;
+			}
+		}
+
 		if let Some(SizeSnapData { manager, points, snap_data }) = snap {
 			let view_to_doc = snap_data.document.metadata().document_to_viewport.inverse();
 			let bounds_to_doc = view_to_doc * transform;
@@ -176,19 +664,89 @@ impl SelectedEdges {
 			max = pivot - (pivot - max) * best_scale_factor;
 		}
 
-		(min, max - min)
+		(min, max - min, unconstrained_size)
 	}
 
-	/// Calculates the required scaling to resize the bounding box
-	pub fn bounds_to_scale_transform(&self, position: DVec2, size: DVec2) -> (DAffine2, DVec2) {
+	/// Moves all four edges outwards by the same distance so the outline of the expanded frame passes through the local space mouse position.
+	/// Grabbing the frame anywhere on its outline therefore starts without a jump. The size of the frame is never made negative.
+	fn expand_all_edges(&self, mouse: DVec2) -> (DVec2, DVec2) {
+		let min = self.bounds[0].min(self.bounds[1]);
+		let max = self.bounds[0].max(self.bounds[1]);
+		let center = self.default_center_around();
+		let half_size = (max - min) / 2.;
+
+		let outside = (mouse - center).abs() - half_size;
+		let expansion = outside.max_element().max(-half_size.min_element());
+
+		(min - DVec2::splat(expansion), max - min + DVec2::splat(expansion * 2.))
+	}
+
+	/// The dimension a typed value applies to: dragging the left or right edge, or any corner, targets the width and dragging the top or bottom edge targets the height
+	pub fn active_dimension(&self) -> Option<ResizeDimension> {
+		if self.left || self.right {
+			Some(ResizeDimension::Width)
+		} else if self.top || self.bottom {
+			Some(ResizeDimension::Height)
+		} else {
+			None
+		}
+	}
+
+	/// Computes the position and size of the bounds when the given dimension of the original bounds is set to an exact value, in their local space.
+	/// The edges opposite to those dragged stay fixed (or the center, when an axis has both of its edges selected), and corner drags scale the other dimension proportionally.
+	/// Returns `None` for a negative or non-finite value, a dimension that isn't being dragged, or a corner drag of a zero sized dimension.
+	pub fn resize_to(&self, dimension: ResizeDimension, value: f64) -> Option<(DVec2, DVec2)> {
+		if !value.is_finite() || value < 0. {
+			return None;
+		}
+		let (vertical, horizontal) = (self.top || self.bottom, self.left || self.right);
+		let (axis, dragged) = match dimension {
+			ResizeDimension::Width => (0, horizontal),
+			ResizeDimension::Height => (1, vertical),
+		};
+		if !dragged {
+			return None;
+		}
+
+		let old_size = self.bounds[1] - self.bounds[0];
+		let mut size = old_size;
+		size[axis] = value * old_size[axis].signum();
+		if vertical && horizontal {
+			let factor = value / old_size[axis].abs();
+			if !factor.is_finite() {
+				return None;
+			}
+			size[1 - axis] *= factor;
+		}
+
+		let mut min = self.bounds[0];
+		for (axis, min_dragged, max_dragged) in [(0, self.left, self.right), (1, self.top, self.bottom)] {
+			min[axis] = match (min_dragged, max_dragged) {
+				(true, true) => self.default_center_around()[axis] - size[axis] / 2.,
+				(true, false) => self.bounds[1][axis] - size[axis],
+				_ => self.bounds[0][axis],
+			};
+		}
+		Some((min, size))
+	}
+
+	/// The scale factor along each axis from the original bounds to `new_size`, such as for showing the resize as a percentage.
+	/// Axes along which the original bounds have no size can't be scaled, so their factor is 1.
+	pub fn scale_factor(&self, new_size: DVec2) -> DVec2 {
 		let old_size = self.bounds[1] - self.bounds[0];
-		let mut enlargement_factor = size / old_size;
+		let mut enlargement_factor = new_size / old_size;
 		if !enlargement_factor.x.is_finite() || old_size.x.abs() < f64::EPSILON * 1000. {
 			enlargement_factor.x = 1.;
 		}
 		if !enlargement_factor.y.is_finite() || old_size.y.abs() < f64::EPSILON * 1000. {
 			enlargement_factor.y = 1.;
 		}
+		enlargement_factor
+	}
+
+	/// Calculates the required scaling to resize the bounding box
+	pub fn bounds_to_scale_transform(&self, position: DVec2, size: DVec2) -> (DAffine2, DVec2) {
+		let enlargement_factor = self.scale_factor(size);
 		let mut pivot = (self.bounds[0] * enlargement_factor - position) / (enlargement_factor - DVec2::splat(1.));
 		if !pivot.x.is_finite() {
 			pivot.x = 0.;
@@ -198,6 +756,136 @@ impl SelectedEdges {
 		}
 		(DAffine2::from_scale(enlargement_factor), pivot)
 	}
+
+	/// Sets what the children of the selection should apply the resize to, which is returned alongside the transform by `resize_transform`
+	pub fn set_resize_application(&mut self, resize_application: ResizeApplication) {
+		self.resize_application = resize_application;
+	}
+
+	/// The transform in the local space of the bounds which resizes them to the `position` and `size` from `new_size`, combining the scale and pivot of `bounds_to_scale_transform`,
+	/// along with how the children of the selection should apply it
+	pub fn resize_transform(&self, position: DVec2, size: DVec2) -> (DAffine2, ResizeApplication) {
+		let (scale, pivot) = self.bounds_to_scale_transform(position, size);
+		let pivot = DAffine2::from_translation(pivot);
+		(pivot * scale * pivot.inverse(), self.resize_application)
+	}
+
+	/// Calculates the scale which resizes the bounds to `new_size` while keeping `fixed` stationary, where `fixed` is any point in the same space as the bounds rather than necessarily a corner.
+	/// Along an axis where the bounds have no size, no scale factor can be derived so that axis is left unscaled.
+	pub fn resize_keeping_fixed(&self, fixed: DVec2, new_size: DVec2) -> DAffine2 {
+		let enlargement_factor = self.scale_factor(new_size);
+		DAffine2::from_translation(fixed) * DAffine2::from_scale(enlargement_factor) * DAffine2::from_translation(-fixed)
+	}
+}
+
+/// The index in the order of `BoundingBoxManager::evaluate_transform_handle_positions` of the handle in a column (left to right) and row (top to bottom) of the bounds, with no handle at the center
+fn handle_index(column: usize, row: usize) -> Option<usize> {
+	match (column, row) {
+		(0, row) if row < 3 => Some(row),
+		(1, 0) => Some(3),
+		(1, 2) => Some(4),
+		(2, row) if row < 3 => Some(5 + row),
+		_ => None,
+	}
+}
+
+/// Whether a point is within a distance of the box given by its minimum and maximum corners
+fn is_within(point: DVec2, [min, max]: [DVec2; 2], margin: f64) -> bool {
+	point.cmpge(min - margin).all() && point.cmple(max + margin).all()
+}
+
+/// Clips a line segment to the box given by its minimum and maximum corners with the Liang–Barsky algorithm, returning `None` if none of it is inside
+fn clip_segment(start: DVec2, end: DVec2, [min, max]: [DVec2; 2]) -> Option<[DVec2; 2]> {
+	let delta = end - start;
+	let (mut entry, mut exit) = (0_f64, 1_f64);
+	for (p, q) in [(-delta.x, start.x - min.x), (delta.x, max.x - start.x), (-delta.y, start.y - min.y), (delta.y, max.y - start.y)] {
+		if p == 0. {
+			// Parallel to this side of the box, so it's either entirely inside or outside of it
+			if q < 0. {
+				return None;
+			}
+		} else if p < 0. {
+			entry = entry.max(q / p);
+		} else {
+			exit = exit.min(q / p);
+		}
+	}
+	(entry < exit).then(|| [start + delta * entry, start + delta * exit])
+}
+
+/// Finds the new position of the edge opposite to the dragged one so that both scale by the same factor about the anchor.
+/// Returns `None` if the dragged edge lies on the anchor, since no scale factor can then be derived.
+fn scale_about_anchor(anchor: f64, dragged_old: f64, dragged_new: f64, opposite_old: f64) -> Option<f64> {
+	let scale = (dragged_new - anchor) / (dragged_old - anchor);
+	scale.is_finite().then(|| anchor + (opposite_old - anchor) * scale)
+}
+
+/// How `format_dimension` writes a length, such as whole pixels for pixel work or millimeters with decimals for print work
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimensionFormat {
+	/// The number of decimal places to round to, with 0 rounding to the nearest whole unit
+	pub decimals: usize,
+	/// Appended directly after the number, so it should start with a space if one is wanted
+	pub unit: String,
+	/// Groups the digits of the whole part in threes with this separator
+	pub thousands_separator: Option<char>,
+}
+
+impl Default for DimensionFormat {
+	fn default() -> Self {
+		Self {
+			decimals: 0,
+			unit: "px".to_string(),
+			thousands_separator: None,
+		}
+	}
+}
+
+/// Which axes the dimension readout measures the width and height of the cage along
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReadoutAxes {
+	/// Along the rotated axes of the cage itself, which is the size being edited and doesn't change while rotating
+	#[default]
+	Local,
+	/// Along the horizontal and vertical axes of the viewport, which gives the size of the axis-aligned box around the rotated cage
+	Aligned,
+}
+
+/// Writes a length rounded and labelled according to the `format`, such as `1,024px` or `12.50 mm`. Non-finite lengths are written as they are.
+pub fn format_dimension(value: f64, format: &DimensionFormat) -> String {
+	let DimensionFormat { decimals, unit, thousands_separator } = format;
+	if !value.is_finite() {
+		return format!("{value}{unit}");
+	}
+
+	let rounded = format!("{:.*}", *decimals, value.abs());
+	// A value which rounds to zero is written without a minus sign
+	let negative = value < 0. && rounded.chars().any(|digit| digit.is_ascii_digit() && digit != '0');
+	let (whole, fraction) = rounded.split_at(rounded.find('.').unwrap_or(rounded.len()));
+
+	let whole = match thousands_separator {
+		Some(separator) => whole.chars().enumerate().fold(String::new(), |mut grouped, (index, digit)| {
+			if index > 0 && (whole.len() - index) % 3 == 0 {
+				grouped.push(*separator);
+			}
+			grouped.push(digit);
+			grouped
+		}),
+		None => whole.to_string(),
+	};
+	format!("{}{whole}{fraction}{unit}", if negative { "-" } else { "" })
+}
+
+/// A label for a width/height ratio, naming the common ones
+pub fn ratio_name(ratio: f64) -> String {
+	let golden_ratio = (1. + 5_f64.sqrt()) / 2.;
+	let named = [(1., "1:1"), (4. / 3., "4:3"), (3. / 4., "3:4"), (16. / 9., "16:9"), (9. / 16., "9:16"), (golden_ratio, "Golden ratio")];
+	match named.iter().find(|(named_ratio, _)| (named_ratio - ratio).abs() < 1e-6) {
+		Some((_, name)) => name.to_string(),
+		None => format!("{ratio:.3}:1"),
+	}
 }
 
 /// Aligns the mouse position to the closest axis
@@ -257,47 +945,1213 @@ pub fn snap_drag(start: DVec2, current: DVec2, axis_align: bool, snap_data: Snap
 	document.metadata().document_to_viewport.transform_vector2(offset)
 }
 
+/// How a child of the selection, such as a vector path, should apply the transform of a resize, see `SelectedEdges::resize_transform`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResizeApplication {
+	/// Transform the whole child, so the anchor points and the handles scale alike
+	#[default]
+	Full,
+	/// Move the anchor points with the transform but keep the offsets of their handles unchanged, so the handle lengths aren't distorted
+	AnchorsOnly,
+	/// Scale the offsets of the handles from their anchor points but keep the anchor points in place
+	HandlesOnly,
+}
+
+impl ResizeApplication {
+	/// The position of an anchor point after applying the transform
+	pub fn anchor(self, transform: DAffine2, anchor: DVec2) -> DVec2 {
+		match self {
+			Self::Full | Self::AnchorsOnly => transform.transform_point2(anchor),
+			Self::HandlesOnly => anchor,
+		}
+	}
+
+	/// The position of a handle after applying the transform, given the position of its anchor point before it
+	pub fn handle(self, transform: DAffine2, anchor: DVec2, handle: DVec2) -> DVec2 {
+		match self {
+			Self::Full => transform.transform_point2(handle),
+			Self::AnchorsOnly => transform.transform_point2(anchor) + (handle - anchor),
+			Self::HandlesOnly => anchor + transform.transform_vector2(handle - anchor),
+		}
+	}
+}
+
+/// The modifier keys which affect a resize, see `BoundingBoxManager::trial_resize`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResizeModifiers {
+	/// Resize symmetrically about the center
+	pub center: bool,
+	/// Keep the aspect ratio
+	pub constrain: bool,
+}
+
+/// How `BoundingBoxManager::rotation_angle` snaps the angle when snapping is enabled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RotationSnapMode {
+	/// Snap the rotation applied by the drag to increments of `ROTATE_SNAP_ANGLE`
+	#[default]
+	Increment,
+	/// Snap the resulting orientation of the cage to the nearest multiple of 90°, so its edges end up horizontal and vertical
+	Level,
+}
+
+/// How `BoundingBoxManager::compute_rotation` snaps the angle of a rotation, with all options off by default.
+/// A candidate angle within its tolerance takes precedence, otherwise the increment and then whole degree snapping apply in turn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RotationSnap {
+	/// Snap to multiples of this angle in radians, such as `ROTATE_SNAP_ANGLE` converted to radians
+	pub increment: Option<f64>,
+	/// Round to a whole number of degrees, such as so the typed value in a panel matches the result
+	pub whole_degrees: bool,
+	/// Specific angles in radians to snap to when the rotation is within `candidate_tolerance` of them, such as the rotations which align with another object
+	pub candidates: Vec<f64>,
+	/// How far in radians the rotation may be from a candidate to snap to it
+	pub candidate_tolerance: f64,
+}
+
+/// Maps the numeric keypad keys to the nine pivot positions in their layout on the keypad, so 7 is the top left, 5 is the center and 3 is the bottom right
+pub fn numpad_pivot_position(digit: u8) -> Option<PivotPosition> {
+	Some(match digit {
+		7 => PivotPosition::TopLeft,
+		8 => PivotPosition::TopCenter,
+		9 => PivotPosition::TopRight,
+		4 => PivotPosition::CenterLeft,
+		5 => PivotPosition::Center,
+		6 => PivotPosition::CenterRight,
+		1 => PivotPosition::BottomLeft,
+		2 => PivotPosition::BottomCenter,
+		3 => PivotPosition::BottomRight,
+		_ => return None,
+	})
+}
+
+/// The part of the band surrounding the bounds, used for rotating, that is under the cursor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotateRegion {
+	/// Near one of the four corners of the bounds
+	Corner,
+	/// Along an edge, away from the corners
+	Edge,
+}
+
+/// How the size of the handles drawn on the bounding box is determined
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandleScaleMode {
+	/// The handles keep the same size on screen regardless of the zoom or the scale of the selection
+	#[default]
+	ConstantScreen,
+	/// The handles scale along with the transform of the cage, such as for previewing how an object will look
+	ProportionalToObject,
+}
+
+/// Whether the cursor last reported by `BoundingBoxManager::update_cursor` was for resizing or rotating
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CursorRegion {
+	Resize,
+	Rotate,
+}
+
+/// The shape each transform handle is drawn as, which tools can vary to communicate their mode. Hit testing doesn't depend on the shape.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandleShape {
+	#[default]
+	Square,
+	Circle,
+	Diamond,
+}
+
+/// The operation which dragging one of the eight transform handles performs, see `BoundingBoxManager::handle_layout`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandleRole {
+	/// Resizes along both axes by dragging a corner
+	ResizeCorner,
+	/// Resizes along one axis by dragging the middle of an edge
+	ResizeEdge,
+}
+
+/// Viewport space points just outside the cage for placing labels and badges, see `BoundingBoxManager::label_anchors`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LabelAnchors {
+	/// Beyond the midpoints of the top, right, bottom and left edges of the bounds, in that order
+	pub edges: [DVec2; 4],
+	/// Beyond the top left, top right, bottom right and bottom left corners of the bounds, in the same order as `BoundingBoxManager::corners`
+	pub corners: [DVec2; 4],
+	/// The unit directions away from the cage which `edges` are offset along
+	pub edge_directions: [DVec2; 4],
+	/// The unit directions away from the cage which `corners` are offset along
+	pub corner_directions: [DVec2; 4],
+}
+
 /// Contains info on the overlays for the bounding box and transform handles
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoundingBoxManager {
 	pub bounds: [DVec2; 2],
 	pub transform: DAffine2,
 	pub original_bound_transform: DAffine2,
 	pub selected_edges: Option<SelectedEdges>,
+	// The layer transforms are only meaningful for the document they were recorded from, so they aren't serialized
+	#[cfg_attr(feature = "tool-state-serde", serde(skip))]
 	pub original_transforms: OriginalTransforms,
 	pub opposite_pivot: DVec2,
 	pub center_of_transformation: DVec2,
+	/// Draw the handles as squares aligned to the screen axes rather than following the (possibly rotated or skewed) axes of the cage
+	pub screen_aligned_handles: bool,
+	/// The lowest rotation in radians, relative to the orientation at the start of the drag, that the selection may be rotated to
+	pub min_angle: Option<f64>,
+	/// The highest rotation in radians, relative to the orientation at the start of the drag, that the selection may be rotated to
+	pub max_angle: Option<f64>,
+	/// Print the internal state of the cage as text next to the selection, for diagnosing transform issues
+	pub debug: bool,
+	pub handle_scale_mode: HandleScaleMode,
+	/// Distance in viewport space by which the handles are drawn and grabbed outside the bounds, keeping them clear of small selections
+	pub exterior_handle_offset: f64,
+	/// Only allow rotating from the band near the corners, not along the edges between them
+	pub corner_only_rotation: bool,
+	/// The rotation in radians applied so far by the current rotate drag, set by the tool while rotating
+	pub applied_rotation: Option<f64>,
+	/// Whether a rotate drag is in progress, see `begin_rotation` and `end_rotation`
+	pub rotating: bool,
+	/// The angle in radians from the center of transformation to the cursor where the current rotate drag started, which `rotation_delta` is measured from
+	pub rotation_start_angle: Option<f64>,
+	/// Draw a compass around the center of transformation while rotating, with marks every 90° from the original orientation and a needle at the current orientation
+	pub show_rotation_compass: bool,
+	/// Radius of the rotation compass in viewport space
+	pub rotation_compass_radius: f64,
+	/// Draw an arc from the original to the current orientation of the local x axis while rotating, labelled with the angle, see `rotation_arc`
+	pub show_rotation_arc: bool,
+	/// The device pixel ratio of the display, which scales the viewport space distances for grabbing the handles and the rotation band so they stay the same physical size
+	pub device_scale: f64,
+	/// The snap engaged by the previous update of the current drag, see `update_engaged_snap`
+	pub engaged_snap: Option<SnapEvent>,
+	/// A value typed in while resizing which replaces the dragged dimension, see `SelectedEdges::active_dimension`
+	pub numeric_override: Option<f64>,
+	/// Draw a dark halo around each handle so it stays visible against busy artwork
+	pub handle_halo: bool,
+	pub rotation_snap_mode: RotationSnapMode,
+	/// Make the band for rotating a constant distance from the outline of the bounds, with rounded corners, rather than extending further on the diagonals at the corners
+	pub rounded_rotate_band: bool,
+	/// Keep a guide line through the pivot at the orientation of the last snapped rotation, until cleared at the start of the next transform
+	pub show_angle_guide: bool,
+	/// The viewport position and angle in radians of the persistent angle guide, see `update_angle_guide`
+	pub angle_guide: Option<(DVec2, f64)>,
+	/// Viewport space centroid of the selected objects, which symmetric scaling happens about instead of the center of the bounds, see `set_centroid_from`
+	pub centroid: Option<DVec2>,
+	/// Viewport space radius around the center of transformation within which moving the cursor doesn't change the rotation
+	pub rotation_dead_zone: f64,
+	/// Whether `render_overlays` draws anything, so overlays can be hidden during playback or export without the caller skipping the call
+	pub visible: bool,
+	/// The ratio the current resize has locked to, set by the tool from `SelectedEdges::locked_ratio` so it can be labelled on the cage
+	pub locked_ratio: Option<f64>,
+	/// The corner radius in the local space of the bounds when editing a rounded rectangle, which shows the four radius handles, see `radius_handle_positions`
+	pub corner_radius: Option<f64>,
+	/// The shape drawn for each handle, in the same order as `evaluate_transform_handle_positions`
+	pub handle_shapes: [HandleShape; 8],
+	/// Draw a guide along the axis a move is locked to while axis aligning, through the point where the drag started
+	pub show_axis_guide: bool,
+	/// The viewport start point and direction of the axis the current move is locked to, see `update_axis_guide`
+	pub axis_guide: Option<(DVec2, DVec2)>,
+	/// Viewport space distance the cursor must move from the last accepted position before a resize or rotation updates, filtering out sub-pixel jitter, see `steady_cursor`
+	pub min_move_threshold: f64,
+	/// The cursor position last accepted by `steady_cursor` during the current drag
+	pub steadied_cursor: Option<DVec2>,
+	/// Keep resizing and rotating with decaying speed after a quick drag is released, see `record_velocity` and `step_inertia`
+	pub inertia: bool,
+	/// The speed of the drag as the rate of change per second of the natural logarithm of the scale along the local axes, and of the rotation in radians
+	pub inertia_velocity: Option<(DVec2, f64)>,
+	/// The cage transforms at points during the current drag which it can be rolled back to, recorded each time a snap engages or by the tool, see `rollback_to_checkpoint`
+	pub checkpoints: Vec<DAffine2>,
+	/// Keep a pivot placed with `set_pivot` inside the bounds, so rotating and scaling always happen about a point within the selection
+	pub clamp_pivot_to_bounds: bool,
+	/// The fill and stroke color of the handle at the fixed point of the resize in progress, see `SelectedEdges::opposite_handle`
+	pub anchor_handle_color: String,
+	/// The fill color of the handle being dragged by the resize in progress, see `SelectedEdges::grabbed_handle`
+	pub grabbed_handle_color: String,
+	/// Snap rotations from trackpad gestures with `snap_rotation_angle`, since gestures don't come with the modifier keys that enable snapping for drags
+	pub snap_gesture_rotation: bool,
+	/// The raw rotation in radians accumulated from the gesture deltas since the last `end_rotation`, see `apply_gesture_rotation`
+	pub gesture_rotation: f64,
+	/// Widen the band for rotating to this fraction of the smaller side of the cage in viewport space, when that is further out than `BOUNDS_ROTATE_THRESHOLD`, see `rotate_threshold`
+	pub rotate_threshold_fraction: Option<f64>,
+	/// The visible area of the viewport as its minimum and maximum corners, outside of which `render_overlays` skips the handles and clips the outline
+	pub viewport_clip: Option<[DVec2; 2]>,
+	/// Label the width and height of the bounds below the cage while resizing or rotating, written with this format, see `readout_dimensions`
+	pub dimension_readout: Option<DimensionFormat>,
+	/// The axes the dimension readout measures along, see `readout_dimensions`
+	pub readout_axes: ReadoutAxes,
+	/// Instead of skipping handles outside the `viewport_clip`, draw them as diamonds pinned to its edge, showing where they can be found. Hit testing still uses the real positions.
+	pub pin_handles_to_viewport: bool,
+	/// Viewport space radius of the rounded corners of the drawn outline, to match rounded content. This is only cosmetic and doesn't affect hit testing.
+	pub outline_corner_radius: f64,
+	/// Cursors shown instead of the resize cursor while hovering each handle, in the same order as `evaluate_transform_handle_positions`, see `set_handle_cursor`
+	pub handle_cursors: [Option<MouseCursorIcon>; 8],
+	/// The viewport position where the current resize or rotate drag was pressed, see `is_drag`
+	pub press_position: Option<DVec2>,
+	/// The viewport space unit direction the cursor first moved in from the `press_position`, see `lock_drag_direction`
+	pub drag_direction: Option<DVec2>,
+	/// Viewport space distance below which the handles in the middle of the edges are hidden, so a tiny selection shows only its corner handles, see `hidden_edge_handles`
+	pub min_handle_spacing: f64,
+	/// Whether the resize in progress is constrained to the aspect ratio, set by the tool from the modifier key so the overlays can reflect it
+	pub constrain_active: bool,
+	/// Hide the handles in the middle of the edges while `constrain_active`, since they can't resize a single axis then, apart from the dragged and fixed ones
+	pub hide_constrained_handles: bool,
+	/// Viewport space distance the cursor must go past the boundary between resizing and rotating before `update_cursor` switches between them, which stops the cursor flickering along it
+	pub cursor_hysteresis: f64,
+	/// The region of the cursor last reported by `update_cursor`
+	pub cursor_region: Option<CursorRegion>,
+}
+
+/// The geometry of a cage and the resize in progress, which `BoundingBoxManager::snapshot` captures so tools can try out a change and go back with `restore`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "tool-state-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoundingBoxManagerState {
+	pub bounds: [DVec2; 2],
+	pub transform: DAffine2,
+	pub original_bound_transform: DAffine2,
+	pub selected_edges: Option<SelectedEdges>,
+	pub opposite_pivot: DVec2,
+	pub center_of_transformation: DVec2,
+}
+
+impl Default for BoundingBoxManager {
+	fn default() -> Self {
+		Self {
+			bounds: Default::default(),
+			transform: Default::default(),
+			original_bound_transform: Default::default(),
+			selected_edges: None,
+			original_transforms: Default::default(),
+			opposite_pivot: Default::default(),
+			center_of_transformation: Default::default(),
+			screen_aligned_handles: false,
+			min_angle: None,
+			max_angle: None,
+			debug: false,
+			handle_scale_mode: Default::default(),
+			exterior_handle_offset: 0.,
+			corner_only_rotation: false,
+			applied_rotation: None,
+			rotating: false,
+			rotation_start_angle: None,
+			show_rotation_compass: false,
+			rotation_compass_radius: BOUNDS_ROTATION_COMPASS_RADIUS,
+			show_rotation_arc: false,
+			device_scale: 1.,
+			engaged_snap: None,
+			numeric_override: None,
+			handle_halo: false,
+			rotation_snap_mode: RotationSnapMode::default(),
+			rounded_rotate_band: false,
+			show_angle_guide: false,
+			angle_guide: None,
+			centroid: None,
+			rotation_dead_zone: 0.,
+			visible: true,
+			locked_ratio: None,
+			corner_radius: None,
+			handle_shapes: [HandleShape::Square; 8],
+			show_axis_guide: false,
+			axis_guide: None,
+			min_move_threshold: 0.,
+			steadied_cursor: None,
+			inertia: false,
+			inertia_velocity: None,
+			checkpoints: Vec::new(),
+			clamp_pivot_to_bounds: false,
+			anchor_handle_color: COLOR_OVERLAY_BLUE.to_string(),
+			grabbed_handle_color: COLOR_OVERLAY_YELLOW.to_string(),
+			snap_gesture_rotation: false,
+			gesture_rotation: 0.,
+			rotate_threshold_fraction: None,
+			viewport_clip: None,
+			dimension_readout: None,
+			readout_axes: ReadoutAxes::default(),
+			pin_handles_to_viewport: false,
+			outline_corner_radius: 0.,
+			handle_cursors: [None; 8],
+			press_position: None,
+			drag_direction: None,
+			min_handle_spacing: 0.,
+			constrain_active: false,
+			hide_constrained_handles: false,
+			cursor_hysteresis: BOUNDS_CURSOR_HYSTERESIS,
+			cursor_region: None,
+		}
+	}
 }
 
 impl BoundingBoxManager {
+	/// Maps a viewport space point into the local space of the `bounds`
+	pub fn to_local(&self, screen: DVec2) -> DVec2 {
+		self.transform.inverse().transform_point2(screen)
+	}
+
+	/// The position of a viewport space cursor within the `bounds` as fractions of their size, from 0 at the first corner to 1 at the second, such as for showing it as percentages in the status bar.
+	/// Positions outside the bounds extrapolate beyond that range, and an axis along which the bounds have no size always gives 0.
+	pub fn cursor_relative(&self, cursor: DVec2) -> DVec2 {
+		let size = self.bounds[1] - self.bounds[0];
+		let relative = (self.to_local(cursor) - self.bounds[0]) / size;
+		let component = |relative: f64, size: f64| if size.abs() < f64::EPSILON * 1000. { 0. } else { relative };
+		DVec2::new(component(relative.x, size.x), component(relative.y, size.y))
+	}
+
+	/// Maps a point in the local space of the `bounds` into viewport space
+	pub fn to_screen(&self, local: DVec2) -> DVec2 {
+		self.transform.transform_point2(local)
+	}
+
+	/// The transform from the unit square spanning the `bounds`, with 0 at the first corner and 1 at the second, into viewport space.
+	/// Tools can use it to draw overlays laid out in fractions of the cage, such as grids or rule-of-thirds guides, that follow its rotation and skew.
+	pub fn local_to_screen(&self) -> DAffine2 {
+		self.transform * DAffine2::from_translation(self.bounds[0]) * DAffine2::from_scale(self.bounds[1] - self.bounds[0])
+	}
+
 	/// Calculates the transformed handle positions based on the bounding box and the transform
 	pub fn evaluate_transform_handle_positions(&self) -> [DVec2; 8] {
-		let (left, top): (f64, f64) = self.bounds[0].into();
-		let (right, bottom): (f64, f64) = self.bounds[1].into();
+		self.handle_positions_for(self.bounds)
+	}
+
+	/// Calculates the handle positions that candidate local `bounds` would have under the current transform, such as for previewing a resize, in the same order as `evaluate_transform_handle_positions`
+	pub fn handle_positions_for(&self, bounds: [DVec2; 2]) -> [DVec2; 8] {
+		let (left, top): (f64, f64) = bounds[0].into();
+		let (right, bottom): (f64, f64) = bounds[1].into();
 		[
-			self.transform.transform_point2(DVec2::new(left, top)),
-			self.transform.transform_point2(DVec2::new(left, (top + bottom) / 2.)),
-			self.transform.transform_point2(DVec2::new(left, bottom)),
-			self.transform.transform_point2(DVec2::new((left + right) / 2., top)),
-			self.transform.transform_point2(DVec2::new((left + right) / 2., bottom)),
-			self.transform.transform_point2(DVec2::new(right, top)),
-			self.transform.transform_point2(DVec2::new(right, (top + bottom) / 2.)),
-			self.transform.transform_point2(DVec2::new(right, bottom)),
+			self.to_screen(DVec2::new(left, top)),
+			self.to_screen(DVec2::new(left, (top + bottom) / 2.)),
+			self.to_screen(DVec2::new(left, bottom)),
+			self.to_screen(DVec2::new((left + right) / 2., top)),
+			self.to_screen(DVec2::new((left + right) / 2., bottom)),
+			self.to_screen(DVec2::new(right, top)),
+			self.to_screen(DVec2::new(right, (top + bottom) / 2.)),
+			self.to_screen(DVec2::new(right, bottom)),
 		]
 	}
 
-	/// Update the position of the bounding box and transform handles
-	pub fn render_overlays(&mut self, overlay_context: &mut OverlayContext) {
-		overlay_context.quad(self.transform * Quad::from_box(self.bounds), None);
+	/// Interpolates between this cage and the `target` cage for animating transitions, with `t` clamped between 0 and 1.
+	///
+	/// The bounds are interpolated linearly while the transforms are decomposed and interpolated by their scale, rotation (along the shortest direction), and translation
+	/// which avoids the shearing artifacts of interpolating the matrices directly. Any skew in the transforms is therefore not preserved.
+	/// All other state is taken from `self`.
+	pub fn lerp(&self, target: &BoundingBoxManager, t: f64) -> BoundingBoxManager {
+		let t = t.clamp(0., 1.);
+		let (start_scale, start_angle, start_translation) = self.transform.to_scale_angle_translation();
+		let (end_scale, end_angle, end_translation) = target.transform.to_scale_angle_translation();
+		let angle_difference = (end_angle - start_angle + PI).rem_euclid(TAU) - PI;
 
-		for position in self.evaluate_transform_handle_positions() {
-			overlay_context.square(position, Some(6.), None, None);
+		BoundingBoxManager {
+			bounds: [self.bounds[0].lerp(target.bounds[0], t), self.bounds[1].lerp(target.bounds[1], t)],
+			transform: DAffine2::from_scale_angle_translation(start_scale.lerp(end_scale, t), start_angle + angle_difference * t, start_translation.lerp(end_translation, t)),
+			..self.clone()
 		}
 	}
 
-	/// Compute the threshold in viewport space. This only works with affine transforms as it assumes lines remain parallel.
-	fn compute_viewport_threshold(&self, scalar: f64) -> [f64; 2] {
-		let inverse = self.transform.inverse();
+	/// The translation, rotation in radians and scale of the `transform`, such as for showing them in separate fields of a properties panel.
+	/// A reflection is given as a negative horizontal scale, and any skew is left out since it can't be represented by these three parts.
+	pub fn trs(&self) -> (DVec2, f64, DVec2) {
+		let (scale, angle, translation) = self.transform.to_scale_angle_translation();
+		(translation, angle, scale)
+	}
+
+	/// Recomposes the `transform` from the parts given by `trs`, with changes to the rotation and scale happening about the viewport space `pivot` so it stays in place.
+	/// Changing the translation moves the cage by the difference from the current translation, and passing back the unchanged parts of an unskewed transform leaves it as is.
+	pub fn set_trs(&mut self, translation: DVec2, rotation: f64, scale: DVec2, pivot: DVec2) {
+		let (current_translation, _, _) = self.trs();
+		let local_pivot = self.transform.inverse().transform_point2(pivot);
+
+		let transform = DAffine2::from_scale_angle_translation(scale, rotation, translation);
+		let shift = pivot + (translation - current_translation) - transform.transform_point2(local_pivot);
+		self.transform = DAffine2::from_translation(shift) * transform;
+	}
+
+	/// The viewport space rotation about the `pivot` which turns this cage to the same orientation as the `other` cage, such as for aligning the orientation of a selection to another layer.
+	/// When only one of the cages is reflected their axes can't all line up, so the outlines are aligned by the closest of the two turns that are a half turn apart.
+	pub fn match_rotation(&self, other: &BoundingBoxManager, pivot: DVec2) -> DAffine2 {
+		let (_, angle, _) = self.transform.to_scale_angle_translation();
+		let (_, other_angle, _) = other.transform.to_scale_angle_translation();
+
+		let reflected = |transform: DAffine2| transform.matrix2.determinant() < 0.;
+		let period = if reflected(self.transform) != reflected(other.transform) { PI } else { TAU };
+		let delta = (other_angle - angle + period / 2.).rem_euclid(period) - period / 2.;
+
+		DAffine2::from_translation(pivot) * DAffine2::from_angle(delta) * DAffine2::from_translation(-pivot)
+	}
+
+	/// The viewport space rotation about the `pivot` which makes the line from `a` to `b` horizontal, such as for straightening a photo by picking two points which should be level.
+	/// It turns by the smallest angle, so a line drawn from right to left is levelled the same way as one from left to right. Coinciding points give the identity.
+	pub fn level_points(&self, a: DVec2, b: DVec2, pivot: DVec2) -> DAffine2 {
+		let Some(direction) = (b - a).try_normalize() else { return DAffine2::IDENTITY };
+		let correction = (-direction.to_angle() + FRAC_PI_2).rem_euclid(PI) - FRAC_PI_2;
+		DAffine2::from_translation(pivot) * DAffine2::from_angle(correction) * DAffine2::from_translation(-pivot)
+	}
+
+	/// The viewport space reflection across the line through `point` along `direction`, such as a guide at any angle, for mirroring the selection.
+	/// A direction with no length gives no line to reflect across, so the identity is returned.
+	pub fn reflect_across(&self, point: DVec2, direction: DVec2) -> DAffine2 {
+		let Some(direction) = direction.try_normalize() else { return DAffine2::IDENTITY };
+
+		// Reflecting across a line at angle θ is the matrix with columns (cos 2θ, sin 2θ) and (sin 2θ, -cos 2θ)
+		let (cos, sin) = (direction.x * direction.x - direction.y * direction.y, 2. * direction.x * direction.y);
+		let reflection = DAffine2::from_cols(DVec2::new(cos, sin), DVec2::new(sin, -cos), DVec2::ZERO);
+		DAffine2::from_translation(point) * reflection * DAffine2::from_translation(-point)
+	}
+
+	/// Reorders the components of the local `bounds` so that the first corner is the minimum and the second is the maximum, without changing the box they describe.
+	/// Hit testing and resizing handle bounds in either order, but the corner order of `corners` and `evaluate_transform_handle_positions` only matches its description once normalized.
+	pub fn normalize_bounds(&mut self) {
+		self.bounds = [self.bounds[0].min(self.bounds[1]), self.bounds[0].max(self.bounds[1])];
+	}
+
+	/// Whether the first corner of the local `bounds` is the minimum on both axes, see `normalize_bounds`
+	pub fn is_normalized(&self) -> bool {
+		self.bounds[0].cmple(self.bounds[1]).all()
+	}
+
+	/// Grows the local `bounds` so they contain the given viewport space `point`, leaving the transform unchanged.
+	/// The resulting bounds are ordered with the minimum corner first.
+	pub fn expand_to_include(&mut self, point: DVec2) {
+		let point = self.to_local(point);
+		let [min, max] = [self.bounds[0].min(self.bounds[1]), self.bounds[0].max(self.bounds[1])];
+		self.bounds = [min.min(point), max.max(point)];
+	}
+
+	/// The change made to the transform since the start of the current drag, which maps the cage as it was when the drag began onto the cage as it is now.
+	/// Falls back to the identity if the original transform is not invertible.
+	pub fn drag_delta_transform(&self) -> DAffine2 {
+		if self.original_bound_transform.matrix2.determinant() == 0. {
+			return DAffine2::IDENTITY;
+		}
+		self.transform * self.original_bound_transform.inverse()
+	}
+
+	/// Computes the result of resizing with these edges and the mouse at a viewport position, without changing the cage, such as for previewing a drag while hovering.
+	/// Returns the resized bounds in the local space of the current transform, and equivalently the transform which maps the current bounds onto them,
+	/// as the cage would have once the resize is applied to the selection.
+	pub fn trial_resize(&self, edges: &SelectedEdges, mouse: DVec2, modifiers: ResizeModifiers) -> ([DVec2; 2], DAffine2) {
+		let center_around = modifiers.center.then(|| self.center_around());
+		let (position, size) = edges.new_size(mouse, self.transform, center_around, modifiers.constrain, None);
+		let (scale, pivot) = edges.bounds_to_scale_transform(position, size);
+		let pivot = DAffine2::from_translation(pivot);
+		([position, position + size], self.transform * pivot * scale * pivot.inverse())
+	}
+
+	/// The resize which moves one of the `corners` exactly onto the `target` while the opposite corner stays fixed, as a drag of that corner handle would, for placing a corner programmatically.
+	/// The target is in viewport space like the corners, so document positions need mapping through the document to viewport transform first.
+	/// The result is in the form of `trial_resize`. When the corner index is invalid or the target would collapse the bounds onto the fixed corner, the current bounds and transform are returned unchanged.
+	pub fn resize_corner_to(&self, corner_index: usize, target: DVec2) -> ([DVec2; 2], DAffine2) {
+		let unchanged = (self.bounds, self.transform);
+		let Some(&(top, right)) = [(true, false), (true, true), (false, true), (false, false)].get(corner_index) else {
+			return unchanged;
+		};
+
+		let edges = SelectedEdges::new(top, !top, !right, right, self.bounds);
+		let ([min, max], transform) = self.trial_resize(&edges, target, ResizeModifiers::default());
+		if (max - min).abs().cmplt(DVec2::splat(f64::EPSILON * 1000.)).any() || !transform.is_finite() {
+			return unchanged;
+		}
+		([min, max], transform)
+	}
+
+	/// Sets the `centroid` to the average of the viewport space centroids of the selected objects, or clears it if there are none
+	pub fn set_centroid_from(&mut self, centroids: impl IntoIterator<Item = DVec2>) {
+		let (sum, count) = centroids.into_iter().fold((DVec2::ZERO, 0), |(sum, count), centroid| (sum + centroid, count + 1));
+		self.centroid = (count > 0).then(|| sum / count as f64);
+	}
+
+	/// The viewport space point to pass as `center_around` to `SelectedEdges::new_size` for symmetric scaling, which is the `centroid` if there is one and the center of the bounds otherwise
+	pub fn center_around(&self) -> DVec2 {
+		self.centroid.unwrap_or_else(|| self.to_screen((self.bounds[0] + self.bounds[1]) / 2.))
+	}
+
+	/// Formats the `transform` as an SVG `matrix(a,b,c,d,e,f)` transform, whose components are the columns of the affine transform
+	pub fn to_svg_transform(&self) -> String {
+		if self.transform == DAffine2::IDENTITY {
+			return "matrix(1,0,0,1,0,0)".to_string();
+		}
+		format_transform_matrix(self.transform)
+	}
+
+	/// Applies the change made by the current drag of this group cage, as in `drag_delta_transform`, to the viewport space transform of one of its children.
+	/// The delta already maps the group around its pivot, so every child keeps its position relative to the group. Children only become skewed by a
+	/// non-uniform group scale if they are rotated relative to the axes it scales along, just as they would as part of the group.
+	pub fn apply_delta_to(&self, child_transform: DAffine2) -> DAffine2 {
+		self.drag_delta_transform() * child_transform
+	}
+
+	/// The viewport position of one of the nine anchors of the bounds, or `None` for `PivotPosition::None`
+	pub fn pivot_for_anchor(&self, anchor: PivotPosition) -> Option<DVec2> {
+		let normalized: Option<DVec2> = anchor.into();
+		normalized.map(|normalized| self.to_screen(self.bounds[0] + (self.bounds[1] - self.bounds[0]) * normalized))
+	}
+
+	/// Snaps a viewport space pivot to the nearest target within `SNAP_POINT_TOLERANCE`, out of the handles, the center of the bounds
+	/// and the intersections of the guides, given by the viewport x positions of the vertical guides and y positions of the horizontal ones.
+	/// Returns the pivot unchanged if no target is close enough.
+	pub fn snap_pivot(&self, pivot: DVec2, vertical_guides: &[f64], horizontal_guides: &[f64]) -> DVec2 {
+		let handles = self.evaluate_transform_handle_positions();
+		let center = self.to_screen((self.bounds[0] + self.bounds[1]) / 2.);
+		let intersections = vertical_guides.iter().flat_map(|&x| horizontal_guides.iter().map(move |&y| DVec2::new(x, y)));
+
+		handles
+			.into_iter()
+			.chain([center])
+			.chain(intersections)
+			.map(|target| (target, target.distance(pivot)))
+			.filter(|&(_, distance)| distance < SNAP_POINT_TOLERANCE)
+			.min_by(|a, b| a.1.total_cmp(&b.1))
+			.map_or(pivot, |(target, _)| target)
+	}
+
+	/// Moves the center of transformation to a viewport space point, first clamping it to the nearest point within the bounds if `clamp_pivot_to_bounds` is enabled.
+	/// Returns the pivot as it was placed.
+	pub fn set_pivot(&mut self, pivot: DVec2) -> DVec2 {
+		let pivot = if self.clamp_pivot_to_bounds {
+			let min = self.bounds[0].min(self.bounds[1]);
+			let max = self.bounds[0].max(self.bounds[1]);
+			self.to_screen(self.to_local(pivot).clamp(min, max))
+		} else {
+			pivot
+		};
+		self.center_of_transformation = pivot;
+		pivot
+	}
+
+	/// Moves the center of transformation to an anchor of the bounds, which also becomes the fixed point of the resize in progress.
+	/// Returns the new center of transformation, or `None` (leaving everything unchanged) for `PivotPosition::None`.
+	pub fn set_pivot_anchor(&mut self, anchor: PivotPosition) -> Option<DVec2> {
+		let pivot = self.pivot_for_anchor(anchor)?;
+		self.center_of_transformation = pivot;
+		if let Some(selected_edges) = &mut self.selected_edges {
+			selected_edges.set_anchor(anchor);
+		}
+		Some(pivot)
+	}
+
+	/// Rounds the local `bounds` to the nearest multiples of `step`, leaving the transform intact.
+	///
+	/// The snapping happens in the local space of the cage. When the transform contains no rotation this aligns the edges with the grid of that space,
+	/// but for a rotated cage only the local rectangle is aligned so the rotation is kept and the edges will generally not lie on axis-aligned grid lines.
+	pub fn snap_bounds_to_grid(&mut self, step: f64) {
+		if !step.is_finite() || step <= 0. {
+			return;
+		}
+		self.bounds = self.bounds.map(|corner| (corner / step).round() * step);
+	}
+
+	/// The four corners of the bounds after being transformed into viewport space, in the order top left, top right, bottom right, bottom left (in local space)
+	pub fn corners(&self) -> [DVec2; 4] {
+		(self.transform * Quad::from_box(self.bounds)).0
+	}
+
+	/// Places a readout, such as the locked ratio label, next to the viewport space `anchor` on the cage so it stays upright and readable whatever the rotation of the cage.
+	/// The returned transform is only a translation, offset by `gap` away from the center of the cage, and the pivot makes the text extend further away from the cage rather than back over it.
+	pub fn readout_placement(&self, anchor: DVec2, gap: f64) -> (DAffine2, [Pivot; 2]) {
+		let [min, max] = self.aabb();
+		let direction = (anchor - (min + max) / 2.).try_normalize().unwrap_or(DVec2::NEG_Y);
+		let pivot = |component: f64| match component {
+			component if component > 0.3 => Pivot::Start,
+			component if component < -0.3 => Pivot::End,
+			_ => Pivot::Middle,
+		};
+		(DAffine2::from_translation(anchor + direction * gap), [pivot(direction.x), pivot(direction.y)])
+	}
+
+	/// The points `margin` pixels outside each edge midpoint and corner of the cage, offset perpendicular to the edges or along the bisector at the corners.
+	/// The directions follow the rotation and skew of the cage, so labels placed there sit outside it, such as with `readout_placement`.
+	pub fn label_anchors(&self, margin: f64) -> LabelAnchors {
+		let corners = self.corners();
+		let center = corners.iter().sum::<DVec2>() / 4.;
+
+		let edge_directions: [DVec2; 4] = std::array::from_fn(|index| {
+			let (start, end) = (corners[index], corners[(index + 1) % 4]);
+			let outward = (start + end) / 2. - center;
+			match (end - start).perp().try_normalize() {
+				Some(normal) if normal.dot(outward) < 0. => -normal,
+				Some(normal) => normal,
+				None => outward.try_normalize().unwrap_or(DVec2::NEG_Y),
+			}
+		});
+		let corner_directions: [DVec2; 4] = std::array::from_fn(|index| {
+			let bisector = edge_directions[(index + 3) % 4] + edge_directions[index];
+			bisector.try_normalize().unwrap_or(edge_directions[index])
+		});
+
+		LabelAnchors {
+			edges: std::array::from_fn(|index| (corners[index] + corners[(index + 1) % 4]) / 2. + edge_directions[index] * margin),
+			corners: std::array::from_fn(|index| corners[index] + corner_directions[index] * margin),
+			edge_directions,
+			corner_directions,
+		}
+	}
+
+	/// Where one of the `corners` would end up if the viewport space `delta` was applied about the center of transformation, such as for drawing motion vectors while previewing a drag.
+	/// Returns `None` if the index isn't one of the four corners.
+	pub fn corner_after(&self, corner_index: usize, delta: DAffine2) -> Option<DVec2> {
+		let corner = *self.corners().get(corner_index)?;
+		let pivot = DAffine2::from_translation(self.center_of_transformation);
+		Some((pivot * delta * pivot.inverse()).transform_point2(corner))
+	}
+
+	/// The area enclosed by the transformed `corners`, found with the shoelace formula so it accounts for any rotation, skew and non-uniform scale.
+	/// It is always positive, even when the transform reflects the cage.
+	pub fn area(&self) -> f64 {
+		let corners = self.corners();
+		let twice_signed_area: f64 = (0..4).map(|index| corners[index].perp_dot(corners[(index + 1) % 4])).sum();
+		twice_signed_area.abs() / 2.
+	}
+
+	/// The viewport space translation and scale which fits the `aabb` of the cage into the `target` rectangle, given by its minimum and maximum corners.
+	/// When preserving the aspect ratio, both axes scale by the smaller factor and the result is centered within the target, leaving a margin on the other axis.
+	/// Along an axis where the cage has no size, nothing can be scaled so the factor is 1, while a target with no size collapses the cage onto it.
+	pub fn fit_into(&self, target: [DVec2; 2], preserve_aspect: bool) -> DAffine2 {
+		let [min, max] = self.aabb();
+		let [target_min, target_max] = [target[0].min(target[1]), target[0].max(target[1])];
+		let (size, target_size) = (max - min, target_max - target_min);
+
+		let factor = |size: f64, target_size: f64| (size > f64::EPSILON * 1000.).then(|| target_size / size);
+		let [x, y] = [factor(size.x, target_size.x), factor(size.y, target_size.y)];
+		let scale = if preserve_aspect {
+			DVec2::splat(x.into_iter().chain(y).reduce(f64::min).unwrap_or(1.))
+		} else {
+			DVec2::new(x.unwrap_or(1.), y.unwrap_or(1.))
+		};
+
+		let target_center = (target_min + target_max) / 2.;
+		DAffine2::from_translation(target_center) * DAffine2::from_scale(scale) * DAffine2::from_translation(-(min + max) / 2.)
+	}
+
+	/// Whether the transformed quads of the two cages intersect, using the separating axis test on their `corners`.
+	/// Cages which only touch along an edge or at a corner, to within a millionth of a viewport pixel, don't count as overlapping.
+	pub fn overlaps(&self, other: &BoundingBoxManager) -> bool {
+		let quads = [self.corners(), other.corners()];
+		let edge_normals = quads.iter().flat_map(|corners| (0..4).map(move |index| (corners[(index + 1) % 4] - corners[index]).perp()));
+
+		let project = |corners: &[DVec2; 4], axis: DVec2| {
+			corners
+				.iter()
+				.map(|corner| corner.dot(axis))
+				.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| (min.min(value), max.max(value)))
+		};
+		for normal in edge_normals {
+			// Degenerate edges have no normal to separate along
+			let Some(axis) = normal.try_normalize() else { continue };
+			let ((min_a, max_a), (min_b, max_b)) = (project(&quads[0], axis), project(&quads[1], axis));
+			if max_a <= min_b + 1e-6 || max_b <= min_a + 1e-6 {
+				return false;
+			}
+		}
+		true
+	}
+
+	/// The smallest axis-aligned box in viewport space containing the (possibly rotated) cage.
+	/// Unlike `bounds`, which is the box in the local space before the transform is applied, this grows as the cage is rotated.
+	pub fn aabb(&self) -> [DVec2; 2] {
+		Quad(self.corners()).bounding_box()
+	}
+
+	/// The handle positions as they are drawn, which are moved outwards by the `exterior_handle_offset` in viewport space.
+	/// The order matches `evaluate_transform_handle_positions`.
+	pub fn displayed_handle_positions(&self) -> [DVec2; 8] {
+		let mut positions = self.evaluate_transform_handle_positions();
+		if self.exterior_handle_offset == 0. {
+			return positions;
+		}
+
+		let [x_axis, y_axis] = self.handle_axes();
+		// Outwards is towards the lower bound on the left and top and towards the upper bound on the right and bottom
+		let sign = (self.bounds[1] - self.bounds[0]).signum();
+		let outwards = [(-1., -1.), (-1., 0.), (-1., 1.), (0., -1.), (0., 1.), (1., -1.), (1., 0.), (1., 1.)];
+		for (position, (x, y)) in positions.iter_mut().zip(outwards) {
+			*position += (x_axis * x * sign.x + y_axis * y * sign.y) * self.exterior_handle_offset;
+		}
+		positions
+	}
+
+	/// Whether the handles in the middle of the left and right edges, and in the middle of the top and bottom edges, are hidden because they are drawn closer than `min_handle_spacing` to the corner handles.
+	/// While hidden, `check_selected_edges` no longer picks out those edges on their own.
+	pub fn hidden_edge_handles(&self) -> [bool; 2] {
+		let positions = self.displayed_handle_positions();
+		[
+			positions[0].distance(positions[1]) < self.min_handle_spacing,
+			positions[0].distance(positions[3]) < self.min_handle_spacing,
+		]
+	}
+
+	/// Where each of the eight handles is drawn in viewport space, in the order of `evaluate_transform_handle_positions`, paired with the operation dragging it performs.
+	/// This gives automated UI tests a stable map of the handles to click.
+	pub fn handle_layout(&self) -> [(DVec2, HandleRole); 8] {
+		let positions = self.displayed_handle_positions();
+		std::array::from_fn(|index| {
+			let role = if [0, 2, 5, 7].contains(&index) { HandleRole::ResizeCorner } else { HandleRole::ResizeEdge };
+			(positions[index], role)
+		})
+	}
+
+	/// The local x and y axes of the cage in viewport space, used to orient the handles so they follow any rotation or skew of the transform
+	pub fn handle_axes(&self) -> [DVec2; 2] {
+		[self.transform.transform_vector2(DVec2::X), self.transform.transform_vector2(DVec2::Y)].map(|axis| axis.normalize_or_zero())
+	}
+
+	/// Update the position of the bounding box and transform handles
+	pub fn render_overlays(&mut self, overlay_context: &mut impl OverlayDraw) {
+		if !self.visible {
+			return;
+		}
+
+		let corners = self.corners();
+		match self.viewport_clip {
+			Some(clip) if !corners.iter().all(|&corner| is_within(corner, clip, 0.)) => {
+				for index in 0..4 {
+					if let Some([start, end]) = clip_segment(corners[index], corners[(index + 1) % 4], clip) {
+						overlay_context.line(start, end, None);
+					}
+				}
+			}
+			_ if self.outline_corner_radius > 0. => overlay_context.rounded_quad(Quad(corners), self.outline_corner_radius, None),
+			_ => overlay_context.quad(Quad(corners), None),
+		}
+
+		let axes = self.handle_axes();
+		let [hide_left_right, hide_top_bottom] = self.hidden_edge_handles();
+		let hide_constrained = self.hide_constrained_handles && self.constrain_active;
+		let active = self.selected_edges.as_ref().map(|edges| [edges.grabbed_handle(), edges.opposite_handle()]).unwrap_or_default();
+		let passes = self.handle_passes();
+		// Skip or pin handles which are entirely outside of the visible area, including the largest pass drawn
+		let margin = passes.iter().map(|&(size, _)| size / 2.).fold(0., f64::max);
+		for (index, (mut position, mut shape)) in self.displayed_handle_positions().into_iter().zip(self.handle_shapes).enumerate() {
+			if (hide_left_right && matches!(index, 1 | 6)) || (hide_top_bottom && matches!(index, 3 | 4)) {
+				continue;
+			}
+			if hide_constrained && matches!(index, 1 | 3 | 4 | 6) && !active.contains(&Some(index)) {
+				continue;
+			}
+			if let Some([min, max]) = self.viewport_clip.filter(|&clip| !is_within(position, clip, margin)) {
+				if !self.pin_handles_to_viewport {
+					continue;
+				}
+				position = position.clamp(min + margin, (max - margin).max(min + margin));
+				shape = HandleShape::Diamond;
+			}
+			for &(size, color) in &passes {
+				let (fill, stroke) = if color.is_some() { (color, color) } else { self.handle_colors(index) };
+				match shape {
+					HandleShape::Square if self.screen_aligned_handles => overlay_context.square(position, Some(size), fill, stroke),
+					HandleShape::Square => overlay_context.oriented_square(position, Some(size), axes, fill, stroke),
+					HandleShape::Circle => overlay_context.circle(position, size / 2., fill, stroke),
+					HandleShape::Diamond => overlay_context.diamond(position, Some(size), fill, stroke),
+				}
+			}
+		}
+
+		for position in self.radius_handle_positions().into_iter().flatten() {
+			overlay_context.circle(position, 3. * self.handle_scale(), None, None);
+		}
+
+		if let Some((ticks, needle)) = self.rotation_compass() {
+			for [start, end] in ticks {
+				overlay_context.line(start, end, None);
+			}
+			overlay_context.line(needle[0], needle[1], Some(COLOR_OVERLAY_YELLOW));
+		}
+
+		if let Some((points, label)) = self.rotation_arc() {
+			for segment in points.windows(2) {
+				overlay_context.line(segment[0], segment[1], Some(COLOR_OVERLAY_YELLOW));
+			}
+			let angle = self.applied_rotation.unwrap_or_default();
+			let (transform, pivot) = self.readout_placement(label, 8.);
+			overlay_context.text(&format!("{:.1}°", angle.to_degrees()), COLOR_OVERLAY_WHITE, Some(COLOR_OVERLAY_SNAP_BACKGROUND), transform, 3., pivot);
+		}
+
+		if let Some(ratio) = self.locked_ratio {
+			let (transform, pivot) = self.readout_placement(self.corners()[0], 8.);
+			overlay_context.text(&ratio_name(ratio), COLOR_OVERLAY_WHITE, Some(COLOR_OVERLAY_SNAP_BACKGROUND), transform, 3., pivot);
+		}
+
+		if let Some(format) = self.dimension_readout.as_ref().filter(|_| self.selected_edges.is_some() || self.is_rotating()) {
+			let [_, _, bottom_right, bottom_left] = self.corners();
+			let (transform, pivot) = self.readout_placement((bottom_left + bottom_right) / 2., 8.);
+			let DVec2 { x: width, y: height } = self.readout_dimensions(self.readout_axes);
+			let text = format!("{} × {}", format_dimension(width, format), format_dimension(height, format));
+			overlay_context.text(&text, COLOR_OVERLAY_WHITE, Some(COLOR_OVERLAY_SNAP_BACKGROUND), transform, 3., pivot);
+		}
+
+		if let Some([start, end]) = self.angle_guide_line() {
+			overlay_context.dashed_line(start, end, None, Some(4.));
+		}
+
+		if let Some([start, end]) = self.axis_guide_line() {
+			overlay_context.line(start, end, None);
+		}
+
+		for [start, end] in self.equal_spacing_lines() {
+			overlay_context.line(start, end, Some(COLOR_OVERLAY_RED));
+		}
+
+		if self.debug {
+			let [min, max] = self.aabb();
+			for (index, line) in self.debug_lines().iter().enumerate() {
+				let transform = DAffine2::from_translation(DVec2::new(min.x, max.y + 20. + index as f64 * 18.));
+				overlay_context.text(line, COLOR_OVERLAY_WHITE, Some(COLOR_OVERLAY_SNAP_BACKGROUND), transform, 3., [Pivot::Start, Pivot::End]);
+			}
+		}
+	}
+
+	/// The width and height of the cage in the units of the local space of the bounds at the start of the drag, following the resize and rotation in progress since then.
+	/// With `ReadoutAxes::Aligned`, these are of the axis-aligned box around the cage instead, assuming the start of the drag had the same scale along both axes.
+	pub fn readout_dimensions(&self, axes: ReadoutAxes) -> DVec2 {
+		let size = (self.bounds[1] - self.bounds[0]).abs();
+		match axes {
+			ReadoutAxes::Local => {
+				let resize = self.original_bound_transform.inverse() * self.transform;
+				DVec2::new(resize.transform_vector2(DVec2::X * size.x).length(), resize.transform_vector2(DVec2::Y * size.y).length())
+			}
+			ReadoutAxes::Aligned => {
+				let [min, max] = self.aabb();
+				(max - min) / self.original_bound_transform.matrix2.determinant().abs().sqrt()
+			}
+		}
+	}
+
+	/// The viewport space lines across the two equal gaps while a resize is stuck to an equal spacing position, see `SelectedEdges::set_spacing_neighbors`.
+	/// They are drawn through the middle of the bounds along the other axis.
+	pub fn equal_spacing_lines(&self) -> Vec<[DVec2; 2]> {
+		let Some(edges) = &self.selected_edges else { return Vec::new() };
+		let center = (edges.bounds[0] + edges.bounds[1]) / 2.;
+		let point = |axis: usize, position: f64| {
+			let mut point = center;
+			point[axis] = position;
+			self.original_bound_transform.transform_point2(point)
+		};
+		let gaps = edges.equal_spacing_gaps();
+		(0..2)
+			.flat_map(|axis| gaps[axis].into_iter().flatten().map(move |[start, end]| [point(axis, start), point(axis, end)]))
+			.collect()
+	}
+
+	/// The viewport positions of the handles controlling the `corner_radius`, if it is set, in the same order as `corners`.
+	/// Each is inset from its corner towards the center by the radius along both local axes, but at least by `BOUNDS_RADIUS_HANDLE_MIN_INSET` in viewport space
+	/// so it stays clear of the corner resize handle, and at most to the center of the bounds.
+	pub fn radius_handle_positions(&self) -> Option<[DVec2; 4]> {
+		let radius = self.corner_radius?;
+		let min = self.bounds[0].min(self.bounds[1]);
+		let max = self.bounds[0].max(self.bounds[1]);
+		let half_size = (max - min) / 2.;
+		let inset = DVec2::splat(radius.max(0.))
+			.max(DVec2::from(self.compute_viewport_threshold(BOUNDS_RADIUS_HANDLE_MIN_INSET)))
+			.min(half_size);
+
+		let corners = [min, DVec2::new(max.x, min.y), max, DVec2::new(min.x, max.y)];
+		Some(corners.map(|corner| {
+			let inward = (min + half_size - corner).signum();
+			self.to_screen(corner + inward * inset)
+		}))
+	}
+
+	/// Check which radius handle is under the cursor, as an index into `radius_handle_positions`.
+	/// Tools should check this before `check_selected_edges`, because radius handles take priority over the resize handles and edges.
+	pub fn check_radius_handle(&self, cursor: DVec2) -> Option<usize> {
+		let threshold = self.select_threshold();
+		self.radius_handle_positions()?
+			.into_iter()
+			.enumerate()
+			.map(|(index, position)| (index, position.distance(cursor)))
+			.filter(|&(_, distance)| distance < threshold)
+			.min_by(|a, b| a.1.total_cmp(&b.1))
+			.map(|(index, _)| index)
+	}
+
+	/// The change in corner radius from dragging the given radius handle from `start` to `current` in viewport space, rather than a change to the bounds.
+	/// Moving the handle diagonally inwards by some distance along both local axes grows the radius by that distance.
+	/// Returns `None` for a handle index that isn't one of the four corners.
+	pub fn radius_delta(&self, handle: usize, start: DVec2, current: DVec2) -> Option<f64> {
+		let min = self.bounds[0].min(self.bounds[1]);
+		let max = self.bounds[0].max(self.bounds[1]);
+		let corner = *[min, DVec2::new(max.x, min.y), max, DVec2::new(min.x, max.y)].get(handle)?;
+		let inward = ((min + max) / 2. - corner).signum();
+		Some((self.to_local(current) - self.to_local(start)).dot(inward) / 2.)
+	}
+
+	/// The size of each square drawn per handle, from back to front, with the color which overrides both its fill and stroke.
+	/// Normally this is just the handle itself in the default colors, which is drawn over a larger dark square when `handle_halo` is enabled.
+	fn handle_passes(&self) -> Vec<(f64, Option<&'static str>)> {
+		let size = 6. * self.handle_scale();
+		let halo = self.handle_halo.then_some((size + BOUNDS_HANDLE_HALO_WIDTH * 2., Some(COLOR_OVERLAY_SNAP_BACKGROUND)));
+		halo.into_iter().chain([(size, None)]).collect()
+	}
+
+	/// The fill and stroke colors of a handle, which highlight the fixed and the dragged handles while resizing and are otherwise the defaults
+	fn handle_colors(&self, index: usize) -> (Option<&str>, Option<&str>) {
+		let Some(selected_edges) = &self.selected_edges else { return (None, None) };
+		if selected_edges.opposite_handle() == Some(index) {
+			(Some(&self.anchor_handle_color), Some(&self.anchor_handle_color))
+		} else if selected_edges.grabbed_handle() == Some(index) {
+			(Some(&self.grabbed_handle_color), None)
+		} else {
+			(None, None)
+		}
+	}
+
+	/// Whether a rotate drag is in progress, like `selected_edges` being set indicates a resize
+	pub fn is_rotating(&self) -> bool {
+		self.rotating
+	}
+
+	/// Called by the tool when a rotate drag starts, once the center of transformation has been set, with the cursor position that grabbed the cage.
+	/// Recording the angle to the cursor means the grabbed point follows the cursor from there without jumping.
+	pub fn begin_rotation(&mut self, cursor: DVec2) {
+		self.rotating = true;
+		self.applied_rotation = None;
+		self.rotation_start_angle = Some((cursor - self.center_of_transformation).to_angle());
+		self.begin_drag(cursor);
+	}
+
+	/// Called by the tool when a resize or rotate drag starts with the cursor position that grabbed the cage, which `steady_cursor`, `is_drag` and `lock_drag_direction` measure from
+	pub fn begin_drag(&mut self, cursor: DVec2) {
+		self.steadied_cursor = Some(cursor);
+		self.press_position = Some(cursor);
+		self.drag_direction = None;
+	}
+
+	/// Records the direction the cursor first moves in once it is `DRAG_THRESHOLD` from the `press_position`, and while `lock` is set, such as by a modifier key,
+	/// projects the cursor onto the line through the press position in that direction. The result is passed on to `SelectedEdges::new_size` so later perpendicular movement is ignored.
+	pub fn lock_drag_direction(&mut self, cursor: DVec2, lock: bool) -> DVec2 {
+		let Some(press) = self.press_position else { return cursor };
+		if self.drag_direction.is_none() && self.is_drag(cursor, DRAG_THRESHOLD) {
+			self.drag_direction = (cursor - press).try_normalize();
+		}
+
+		match self.drag_direction {
+			Some(direction) if lock => press + direction * (cursor - press).dot(direction),
+			_ => cursor,
+		}
+	}
+
+	/// Whether the cursor has moved further than the viewport space `threshold` from the `press_position`, telling a drag which transforms the cage apart from a click on it
+	pub fn is_drag(&self, current: DVec2, threshold: f64) -> bool {
+		self.press_position.is_some_and(|press| press.distance(current) > threshold)
+	}
+
+	/// Filters the cursor position of a resize or rotate drag, returning the last accepted position while the cursor stays within `min_move_threshold` of it.
+	/// Passing the result on to `SelectedEdges::new_size` or `rotation_delta` makes those updates no-ops, since they then give the previous result.
+	pub fn steady_cursor(&mut self, cursor: DVec2) -> DVec2 {
+		match self.steadied_cursor {
+			Some(steadied) if steadied.distance(cursor) < self.min_move_threshold => steadied,
+			_ => *self.steadied_cursor.insert(cursor),
+		}
+	}
+
+	/// Called by the tool when a rotate drag is committed or cancelled, which also clears the `applied_rotation`
+	pub fn end_rotation(&mut self) {
+		self.rotating = false;
+		self.applied_rotation = None;
+		self.rotation_start_angle = None;
+		self.steadied_cursor = None;
+		self.press_position = None;
+		self.drag_direction = None;
+		self.checkpoints.clear();
+		self.gesture_rotation = 0.;
+	}
+
+	/// Captures the bounds, transforms, pivots and the resize in progress, for going back to them with `restore`.
+	/// Unlike `cancel`, this can branch from any point of a drag and doesn't involve the `original_transforms` of the selection.
+	pub fn snapshot(&self) -> BoundingBoxManagerState {
+		BoundingBoxManagerState {
+			bounds: self.bounds,
+			transform: self.transform,
+			original_bound_transform: self.original_bound_transform,
+			selected_edges: self.selected_edges.clone(),
+			opposite_pivot: self.opposite_pivot,
+			center_of_transformation: self.center_of_transformation,
+		}
+	}
+
+	/// Goes back to the state captured by `snapshot`, leaving all other fields of the cage as they are
+	pub fn restore(&mut self, state: BoundingBoxManagerState) {
+		let BoundingBoxManagerState {
+			bounds,
+			transform,
+			original_bound_transform,
+			selected_edges,
+			opposite_pivot,
+			center_of_transformation,
+		} = state;
+		self.bounds = bounds;
+		self.transform = transform;
+		self.original_bound_transform = original_bound_transform;
+		self.selected_edges = selected_edges;
+		self.opposite_pivot = opposite_pivot;
+		self.center_of_transformation = center_of_transformation;
+	}
+
+	/// Cancels the resize or rotate drag in progress, restoring the transform from its start and clearing the state of the drag.
+	/// Returns the transforms recorded at the start of the drag, for the caller to re-apply to the affected layers if it doesn't abort the transaction instead.
+	pub fn cancel(&mut self) -> OriginalTransforms {
+		self.transform = self.original_bound_transform;
+		self.selected_edges = None;
+		self.end_rotation();
+		self.engaged_snap = None;
+		self.numeric_override = None;
+		self.locked_ratio = None;
+		self.constrain_active = false;
+		self.inertia_velocity = None;
+		std::mem::take(&mut self.original_transforms)
+	}
+
+	/// Called by the tool on each update of a resize or rotate drag, if `inertia` is enabled, with the scale along the local axes and the rotation applied since the previous update `dt` seconds ago
+	pub fn record_velocity(&mut self, scale: DVec2, angle: f64, dt: f64) {
+		if !self.inertia || dt <= 0. || scale.cmple(DVec2::ZERO).any() {
+			return;
+		}
+		self.inertia_velocity = Some((DVec2::new(scale.x.ln(), scale.y.ln()) / dt, angle / dt));
+	}
+
+	/// Called by the tool on each frame after the drag is released until it returns `None`, continuing the resize and rotation with the recorded velocity from `record_velocity`.
+	/// The velocity decays exponentially by `BOUNDS_INERTIA_DECAY_RATE` per second and stops once both the scale and rotation rates fall below `BOUNDS_INERTIA_STOP_SPEED`.
+	/// The scale is about the center of the bounds and the rotation is about the center of transformation. Returns the viewport space transform to apply to the selection for this frame, which has also been applied to the cage.
+	pub fn step_inertia(&mut self, dt: f64) -> Option<DAffine2> {
+		let (scale_rate, angular_rate) = self.inertia_velocity?;
+		let decay = (-BOUNDS_INERTIA_DECAY_RATE * dt).exp();
+		let (scale_rate, angular_rate) = (scale_rate * decay, angular_rate * decay);
+		if scale_rate.length() < BOUNDS_INERTIA_STOP_SPEED && angular_rate.abs() < BOUNDS_INERTIA_STOP_SPEED {
+			self.inertia_velocity = None;
+			return None;
+		}
+		self.inertia_velocity = Some((scale_rate, angular_rate));
+
+		let local_center = DAffine2::from_translation((self.bounds[0] + self.bounds[1]) / 2.);
+		let scale = local_center * DAffine2::from_scale(DVec2::new((scale_rate.x * dt).exp(), (scale_rate.y * dt).exp())) * local_center.inverse();
+		let center = DAffine2::from_translation(self.center_of_transformation);
+		let rotation = center * DAffine2::from_angle(angular_rate * dt) * center.inverse();
+
+		let delta = rotation * self.transform * scale * self.transform.inverse();
+		self.transform = delta * self.transform;
+		Some(delta)
+	}
+
+	/// Records the orientation of the cage for the angle guide while rotating, if it is enabled and the rotation is currently snapped, otherwise removing the guide.
+	/// The orientation is that of the local x axis of `original_bound_transform` after the `applied_rotation`.
+	pub fn update_angle_guide(&mut self, snapped: bool) {
+		self.angle_guide = self.applied_rotation.filter(|_| self.show_angle_guide && snapped).map(|angle| {
+			let orientation = self.original_bound_transform.transform_vector2(DVec2::X).to_angle();
+			(self.center_of_transformation, orientation + angle)
+		});
+	}
+
+	/// Removes the persistent angle guide
+	pub fn clear_angle_guide(&mut self) {
+		self.angle_guide = None;
+	}
+
+	/// The endpoints in viewport space of the angle guide line, which extends far in both directions from the pivot
+	pub fn angle_guide_line(&self) -> Option<[DVec2; 2]> {
+		let (pivot, angle) = self.angle_guide?;
+		let extent = DVec2::from_angle(angle) * BOUNDS_ANGLE_GUIDE_EXTENT;
+		Some([pivot - extent, pivot + extent])
+	}
+
+	/// Records the axis that a move from `start` to `current` in viewport space is locked to by `axis_align_drag`, if the guide is enabled and the move is being axis aligned, otherwise removing the guide
+	pub fn update_axis_guide(&mut self, axis_align: bool, start: DVec2, current: DVec2) {
+		let direction = (axis_align_drag(true, current, start) - start).try_normalize();
+		self.axis_guide = direction.filter(|_| self.show_axis_guide && axis_align).map(|direction| (start, direction));
+	}
+
+	/// Removes the guide for the locked axis of the move
+	pub fn clear_axis_guide(&mut self) {
+		self.axis_guide = None;
+	}
+
+	/// The endpoints in viewport space of the guide along the locked axis, which extends far in both directions from the start of the move
+	pub fn axis_guide_line(&self) -> Option<[DVec2; 2]> {
+		let (start, direction) = self.axis_guide?;
+		let extent = direction * BOUNDS_ANGLE_GUIDE_EXTENT;
+		Some([start - extent, start + extent])
+	}
+
+	/// The lines of the rotation compass in viewport space, if it should be drawn: the four marks every 90° starting at the original orientation, followed by the needle.
+	/// The 0° mark spans the whole radius so the original orientation can be told apart from the others.
+	pub fn rotation_compass(&self) -> Option<([[DVec2; 2]; 4], [DVec2; 2])> {
+		let angle = self.applied_rotation.filter(|_| self.show_rotation_compass && self.is_rotating())?;
+		let current = self.transform.transform_vector2(DVec2::X).try_normalize()?;
+		let original = DVec2::from_angle(-angle).rotate(current);
+
+		let center = self.center_of_transformation;
+		let radius = self.rotation_compass_radius;
+		let ticks = [0., 1., 2., 3.].map(|quarter: f64| {
+			let direction = DVec2::from_angle(quarter * FRAC_PI_2).rotate(original);
+			let inner = if quarter == 0. { 0. } else { radius * 0.75 };
+			[center + direction * inner, center + direction * radius]
+		});
+
+		Some((ticks, [center, center + current * radius]))
+	}
+
+	/// The rotation arc in viewport space, if it should be drawn: the points along it from the original to the current orientation about the center of transformation,
+	/// at a radius of `BOUNDS_ROTATION_ARC_RADIUS`, and the anchor for its label. The label goes at the middle of the arc, or just outside the cage if that would be over it.
+	pub fn rotation_arc(&self) -> Option<(Vec<DVec2>, DVec2)> {
+		let angle = self.applied_rotation.filter(|&angle| self.show_rotation_arc && self.is_rotating() && angle != 0.)?;
+		let current = self.transform.transform_vector2(DVec2::X).try_normalize()?;
+		let original = DVec2::from_angle(-angle).rotate(current);
+
+		let center = self.center_of_transformation;
+		let segments = (angle.abs() / 5_f64.to_radians()).ceil().max(1.) as usize;
+		let points = (0..=segments)
+			.map(|segment| center + DVec2::from_angle(angle * segment as f64 / segments as f64).rotate(original) * BOUNDS_ROTATION_ARC_RADIUS)
+			.collect();
+
+		let middle = DVec2::from_angle(angle / 2.).rotate(original);
+		let mut label = center + middle * BOUNDS_ROTATION_ARC_RADIUS;
+		let [min, max] = self.aabb();
+		if is_within(label, [min, max], 0.) {
+			// Move out along the same direction to where it leaves the cage
+			let exit = [0, 1]
+				.map(|axis| match middle[axis] {
+					direction if direction > 0. => (max[axis] - label[axis]) / direction,
+					direction if direction < 0. => (min[axis] - label[axis]) / direction,
+					_ => f64::INFINITY,
+				})
+				.into_iter()
+				.fold(f64::INFINITY, f64::min);
+			label += middle * exit;
+		}
+
+		Some((points, label))
+	}
+
+	/// Describes the bounds, decomposed transform, pivots and active edges, one entry per line of the debug overlay
+	pub fn debug_lines(&self) -> Vec<String> {
+		let (scale, angle, translation) = self.transform.to_scale_angle_translation();
+		let edges = self.selected_edges.as_ref().map_or("None".to_string(), |edges| {
+			let names = [(edges.top, "top"), (edges.bottom, "bottom"), (edges.left, "left"), (edges.right, "right")];
+			names.iter().filter(|(selected, _)| *selected).map(|(_, name)| *name).collect::<Vec<_>>().join(", ")
+		});
+
+		vec![
+			format!("Bounds: [{:.2}, {:.2}] to [{:.2}, {:.2}]", self.bounds[0].x, self.bounds[0].y, self.bounds[1].x, self.bounds[1].y),
+			format!("Rotation: {:.2}°  Scale: [{:.3}, {:.3}]", angle.to_degrees(), scale.x, scale.y),
+			format!("Translation: [{:.2}, {:.2}]", translation.x, translation.y),
+			format!("Opposite pivot: [{:.2}, {:.2}]", self.opposite_pivot.x, self.opposite_pivot.y),
+			format!("Center of transformation: [{:.2}, {:.2}]", self.center_of_transformation.x, self.center_of_transformation.y),
+			format!("Edges: {edges}"),
+		]
+	}
+
+	/// The factor applied to the on-screen size of the handles and their hit-testing threshold by the `handle_scale_mode`
+	pub fn handle_scale(&self) -> f64 {
+		match self.handle_scale_mode {
+			HandleScaleMode::ConstantScreen => 1.,
+			HandleScaleMode::ProportionalToObject => self.transform.matrix2.determinant().abs().sqrt(),
+		}
+	}
+
+	/// Compute the threshold in viewport space. This only works with affine transforms as it assumes lines remain parallel.
+	/// The result is the local space length along each axis which spans `scalar` in the viewport, so under a non-uniform scale the x and y thresholds differ to keep the same on-screen distance.
+	fn compute_viewport_threshold(&self, scalar: f64) -> [f64; 2] {
+		let inverse = self.transform.inverse();
 
 		let viewport_x = self.transform.transform_vector2(DVec2::X).normalize_or_zero() * scalar;
 		let viewport_y = self.transform.transform_vector2(DVec2::Y).normalize_or_zero() * scalar;
@@ -306,76 +2160,2788 @@ impl BoundingBoxManager {
 		[threshold_x, threshold_y]
 	}
 
-	/// Check if the user has selected the edge for dragging (returns which edge in order top, bottom, left, right)
-	pub fn check_selected_edges(&self, cursor: DVec2) -> Option<(bool, bool, bool, bool)> {
-		let cursor = self.transform.inverse().transform_point2(cursor);
+	/// The edge or corner of the bounds nearest to the cursor, however far away it is, unlike `check_selected_edges` which only reports one within the threshold.
+	/// Outside the bounds this is the side the cursor is beyond, or the corner if it is beyond two sides. Inside, it's the single edge closest in viewport space.
+	pub fn nearest_edge(&self, cursor: DVec2) -> EdgeSelection {
+		let cursor = self.to_local(cursor);
+		let min = self.bounds[0].min(self.bounds[1]);
+		let max = self.bounds[0].max(self.bounds[1]);
+
+		let (left, right, top, bottom) = (cursor.x < min.x, cursor.x > max.x, cursor.y < min.y, cursor.y > max.y);
+		if left || right || top || bottom {
+			return EdgeSelection { top, bottom, left, right };
+		}
+
+		// Compare the distances in viewport space so a non-uniform scale doesn't favor one axis
+		let [scale_x, scale_y] = self.compute_viewport_threshold(1.);
+		let distances = [(cursor.y - min.y) / scale_y, (max.y - cursor.y) / scale_y, (cursor.x - min.x) / scale_x, (max.x - cursor.x) / scale_x];
+		let nearest = (0..4).min_by(|&a, &b| distances[a].total_cmp(&distances[b])).unwrap_or_default();
+		EdgeSelection {
+			top: nearest == 0,
+			bottom: nearest == 1,
+			left: nearest == 2,
+			right: nearest == 3,
+		}
+	}
+
+	/// The nearest handle within the `select_threshold` of the cursor, as its index in the order of `evaluate_transform_handle_positions`, with how close the cursor is to it
+	/// from 1 at the center of the handle down to 0 at the threshold, such as for emphasizing the handle gradually. The distances are in viewport space to the handles as drawn.
+	pub fn handle_proximity(&self, cursor: DVec2) -> Option<(usize, f64)> {
+		let threshold = self.select_threshold();
+		let [hide_left_right, hide_top_bottom] = self.hidden_edge_handles();
+		self.displayed_handle_positions()
+			.into_iter()
+			.enumerate()
+			.filter(|&(index, _)| !(hide_left_right && matches!(index, 1 | 6)) && !(hide_top_bottom && matches!(index, 3 | 4)))
+			.map(|(index, position)| (index, position.distance(cursor)))
+			.filter(|&(_, distance)| distance < threshold)
+			.min_by(|a, b| a.1.total_cmp(&b.1))
+			.map(|(index, distance)| (index, 1. - distance / threshold))
+	}
+
+	/// Check if the user has selected the edge for dragging (returns which edges are under the cursor)
+	pub fn check_selected_edges(&self, cursor: DVec2) -> Option<EdgeSelection> {
+		let viewport_cursor = cursor;
+		let cursor = self.to_local(cursor);
 
 		let min = self.bounds[0].min(self.bounds[1]);
 		let max = self.bounds[0].max(self.bounds[1]);
-		let [threshold_x, threshold_y] = self.compute_viewport_threshold(BOUNDS_SELECT_THRESHOLD);
+		let size = max - min;
+		let threshold = self.select_threshold();
+		let [threshold_x, threshold_y] = self.compute_viewport_threshold(threshold);
 
-		if min.x - cursor.x < threshold_x && min.y - cursor.y < threshold_y && cursor.x - max.x < threshold_x && cursor.y - max.y < threshold_y {
-			let mut top = (cursor.y - min.y).abs() < threshold_y;
-			let mut bottom = (max.y - cursor.y).abs() < threshold_y;
-			let mut left = (cursor.x - min.x).abs() < threshold_x;
-			let mut right = (max.x - cursor.x).abs() < threshold_x;
+		// Move the grabbable edges outwards to match the drawn exterior handles
+		let offset = DVec2::from(self.compute_viewport_threshold(self.exterior_handle_offset));
+		let (min, max) = (min - offset, max + offset);
 
-			// Prioritise single axis transformations on very small bounds
-			if cursor.y - min.y + max.y - cursor.y < threshold_y * 2. && (left || right) {
-				top = false;
-				bottom = false;
-			}
-			if cursor.x - min.x + max.x - cursor.x < threshold_x * 2. && (top || bottom) {
-				left = false;
-				right = false;
-			}
+		// Corners are hit based on the real viewport distance to the corner handles, which stays accurate when the cage is rotated or skewed
+		let corner = [(false, false), (true, false), (false, true), (true, true)]
+			.into_iter()
+			.map(|(is_right, is_bottom)| {
+				let corner = DVec2::new(if is_right { max.x } else { min.x }, if is_bottom { max.y } else { min.y });
+				(is_right, is_bottom, self.to_screen(corner).distance(viewport_cursor))
+			})
+			.filter(|&(_, _, distance)| distance < threshold)
+			.min_by(|a, b| a.2.total_cmp(&b.2));
 
-			// On bounds with no width/height, disallow transformation in the relevant axis
-			if (max.x - min.x) < f64::EPSILON * 1000. {
-				left = false;
-				right = false;
-			}
-			if (max.y - min.y) < f64::EPSILON * 1000. {
-				top = false;
-				bottom = false;
-			}
+		let (mut top, mut bottom, mut left, mut right);
+		if let Some((is_right, is_bottom, _)) = corner {
+			(top, bottom, left, right) = (!is_bottom, is_bottom, !is_right, is_right);
+		} else if min.x - cursor.x < threshold_x && min.y - cursor.y < threshold_y && cursor.x - max.x < threshold_x && cursor.y - max.y < threshold_y {
+			top = (cursor.y - min.y).abs() < threshold_y;
+			bottom = (max.y - cursor.y).abs() < threshold_y;
+			left = (cursor.x - min.x).abs() < threshold_x;
+			right = (max.x - cursor.x).abs() < threshold_x;
 
-			if top || bottom || left || right {
-				return Some((top, bottom, left, right));
+			// Outside of the corner handles, the corners of the edge bands only select the edge which is closest relative to its threshold
+			if (top || bottom) && (left || right) {
+				let distance_x = if left { cursor.x - min.x } else { max.x - cursor.x }.abs() / threshold_x;
+				let distance_y = if top { cursor.y - min.y } else { max.y - cursor.y }.abs() / threshold_y;
+				if distance_x < distance_y {
+					top = false;
+					bottom = false;
+				} else {
+					left = false;
+					right = false;
+				}
 			}
+		} else {
+			return None;
+		}
+
+		// Prioritise single axis transformations on very small bounds, unless the handles for them are hidden
+		let [hide_left_right, hide_top_bottom] = self.hidden_edge_handles();
+		if max.y - min.y < threshold_y * 2. && (left || right) && !hide_left_right {
+			top = false;
+			bottom = false;
+		}
+		if max.x - min.x < threshold_x * 2. && (top || bottom) && !hide_top_bottom {
+			left = false;
+			right = false;
+		}
+		if (hide_left_right && !(top || bottom)) || (hide_top_bottom && !(left || right)) {
+			return None;
+		}
+
+		// On bounds with no width/height, disallow transformation in the relevant axis
+		if size.x < f64::EPSILON * 1000. {
+			left = false;
+			right = false;
+		}
+		if size.y < f64::EPSILON * 1000. {
+			top = false;
+			bottom = false;
 		}
 
-		None
+		(top || bottom || left || right).then_some(EdgeSelection { top, bottom, left, right })
 	}
 
 	/// Check if the user is rotating with the bounds
 	pub fn check_rotate(&self, cursor: DVec2) -> bool {
-		let cursor = self.transform.inverse().transform_point2(cursor);
-		let [threshold_x, threshold_y] = self.compute_viewport_threshold(BOUNDS_ROTATE_THRESHOLD);
+		match self.check_rotate_region(cursor) {
+			Some(RotateRegion::Corner) => true,
+			Some(RotateRegion::Edge) => !self.corner_only_rotation,
+			None => false,
+		}
+	}
+
+	/// The viewport space distance from the handles and edges within which they are grabbed, which is `BOUNDS_SELECT_THRESHOLD` scaled by the `handle_scale` and the `device_scale`
+	pub fn select_threshold(&self) -> f64 {
+		BOUNDS_SELECT_THRESHOLD * self.handle_scale() * self.device_scale
+	}
+
+	/// The viewport space width of the band outside the bounds for rotating, which is `BOUNDS_ROTATE_THRESHOLD` scaled by the `device_scale`,
+	/// unless the `rotate_threshold_fraction` of the smaller side of the cage is wider
+	pub fn rotate_threshold(&self) -> f64 {
+		let size = (self.bounds[1] - self.bounds[0]).abs();
+		let viewport_size = DVec2::new(
+			self.transform.transform_vector2(DVec2::X * size.x).length(),
+			self.transform.transform_vector2(DVec2::Y * size.y).length(),
+		);
+		let proportional = self.rotate_threshold_fraction.map_or(0., |fraction| fraction * viewport_size.min_element());
+		(BOUNDS_ROTATE_THRESHOLD * self.device_scale).max(proportional)
+	}
+
+	/// Check which part of the band around the bounds used for rotating is under the cursor
+	pub fn check_rotate_region(&self, cursor: DVec2) -> Option<RotateRegion> {
+		let viewport_cursor = cursor;
+		let cursor = self.to_local(cursor);
+		let threshold = self.rotate_threshold();
+		let [threshold_x, threshold_y] = self.compute_viewport_threshold(threshold);
 
 		let min = self.bounds[0].min(self.bounds[1]);
 		let max = self.bounds[0].max(self.bounds[1]);
 
 		let outside_bounds = (min.x > cursor.x || cursor.x > max.x) || (min.y > cursor.y || cursor.y > max.y);
-		let inside_extended_bounds = min.x - cursor.x < threshold_x && min.y - cursor.y < threshold_y && cursor.x - max.x < threshold_x && cursor.y - max.y < threshold_y;
+		let inside_extended_bounds = if self.rounded_rotate_band {
+			self.distance_to_outline(viewport_cursor) < threshold
+		} else {
+			min.x - cursor.x < threshold_x && min.y - cursor.y < threshold_y && cursor.x - max.x < threshold_x && cursor.y - max.y < threshold_y
+		};
+
+		if !(outside_bounds && inside_extended_bounds) {
+			return None;
+		}
+
+		// Within the rotation threshold of the nearest corner on both axes
+		let near_corner_x = (cursor.x - min.x).abs().min((cursor.x - max.x).abs()) < threshold_x;
+		let near_corner_y = (cursor.y - min.y).abs().min((cursor.y - max.y).abs()) < threshold_y;
+
+		Some(if near_corner_x && near_corner_y { RotateRegion::Corner } else { RotateRegion::Edge })
+	}
+
+	/// The viewport space distance from a point to the nearest edge of the transformed bounds
+	fn distance_to_outline(&self, point: DVec2) -> f64 {
+		let corners = self.corners();
+		(0..4)
+			.map(|index| {
+				let (start, end) = (corners[index], corners[(index + 1) % 4]);
+				let t = (point - start).dot(end - start) / (end - start).length_squared();
+				let t = if t.is_finite() { t.clamp(0., 1.) } else { 0. };
+				point.distance(start.lerp(end, t))
+			})
+			.fold(f64::INFINITY, f64::min)
+	}
+
+	/// Computes the translation for moving the selection by `delta`, the total mouse movement since the start of the drag.
+	/// If `axis_align` is set, the movement is locked to the closest axis in the same way as `axis_align_drag`.
+	/// Otherwise this is the translation by `delta` as is, so it also applies to a single step of a drag which has already been snapped or aligned.
+	pub fn translate(&self, delta: DVec2, axis_align: bool) -> DAffine2 {
+		DAffine2::from_translation(axis_align_drag(axis_align, delta, DVec2::ZERO))
+	}
+
+	/// Computes the angle to rotate the selection by when dragging from `start` to `current` around the center of transformation.
+	/// The angle is optionally snapped with `snap_rotation_angle` and then clamped to the allowed range, so it is the value that should be displayed and applied.
+	/// Within the `rotation_dead_zone`, where tiny movements would cause large jumps, the `applied_rotation` is kept unchanged.
+	pub fn rotation_angle(&self, start: DVec2, current: DVec2, snap_angle: bool) -> f64 {
+		self.rotation_from(start - self.center_of_transformation, current, snap_angle)
+	}
+
+	/// Computes the angle to rotate the selection by for the cursor at `current`, in the same way as `rotation_angle` but from the start recorded by `begin_rotation`.
+	/// This is zero when no rotation has begun.
+	pub fn rotation_delta(&self, current: DVec2, snap_angle: bool) -> f64 {
+		let Some(start_angle) = self.rotation_start_angle else { return 0. };
+		self.rotation_from(DVec2::from_angle(start_angle), current, snap_angle)
+	}
+
+	fn rotation_from(&self, start_offset: DVec2, current: DVec2, snap_angle: bool) -> f64 {
+		if current.distance(self.center_of_transformation) < self.rotation_dead_zone {
+			return self.applied_rotation.unwrap_or_default();
+		}
+
+		let end_offset = current - self.center_of_transformation;
+		let angle = start_offset.angle_to(end_offset);
+
+		let snapped_angle = if snap_angle { self.snap_rotation_angle(angle) } else { angle };
+
+		self.clamp_angle(snapped_angle)
+	}
+
+	/// Computes the angle in radians of rotating about the viewport space `pivot` so the point at `before` ends up in the direction of `after`, snapped according to `snap`.
+	/// The result is clamped to the allowed range, so it can be composed as a rotation about the pivot directly. Points on top of the pivot give no rotation.
+	pub fn compute_rotation(&self, pivot: DVec2, before: DVec2, after: DVec2, snap: RotationSnap) -> f64 {
+		let angle = (before - pivot).angle_to(after - pivot);
+		let mut angle = if angle.is_finite() { angle } else { 0. };
+
+		let candidate = snap
+			.candidates
+			.iter()
+			.map(|&candidate| (candidate, (angle - candidate + PI).rem_euclid(TAU) - PI))
+			.filter(|&(_, difference)| difference.abs() <= snap.candidate_tolerance)
+			.min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()));
+		if let Some((_, difference)) = candidate {
+			angle -= difference;
+		} else {
+			if let Some(increment) = snap.increment.filter(|&increment| increment > 0.) {
+				angle = (angle / increment).round() * increment;
+			}
+			if snap.whole_degrees {
+				angle = angle.to_degrees().round().to_radians();
+			}
+		}
+
+		self.clamp_angle(angle)
+	}
+
+	/// Rotates by a raw angle from a trackpad gesture about the viewport space `pivot`, independently of where the cursor is, for each gesture event.
+	/// The deltas accumulate from the `original_bound_transform`, which the tool sets when the gesture starts, until `end_rotation`. The total is snapped if
+	/// `snap_gesture_rotation` is enabled and clamped to the allowed range like a drag. Returns the rotation about the pivot from the original transforms.
+	pub fn apply_gesture_rotation(&mut self, delta_radians: f64, pivot: DVec2) -> DAffine2 {
+		self.gesture_rotation += delta_radians;
+		let angle = if self.snap_gesture_rotation {
+			self.snap_rotation_angle(self.gesture_rotation)
+		} else {
+			self.gesture_rotation
+		};
+		let angle = self.clamp_angle(angle);
+
+		self.applied_rotation = Some(angle);
+		self.center_of_transformation = pivot;
+		let pivot = DAffine2::from_translation(pivot);
+		let rotation = pivot * DAffine2::from_angle(angle) * pivot.inverse();
+		self.transform = rotation * self.original_bound_transform;
+		rotation
+	}
+
+	/// Snaps a rotation relative to the orientation at the start of the drag according to the `rotation_snap_mode`.
+	/// In `RotationSnapMode::Level`, the orientation is that of the local x axis of `original_bound_transform`.
+	pub fn snap_rotation_angle(&self, angle: f64) -> f64 {
+		match self.rotation_snap_mode {
+			RotationSnapMode::Increment => {
+				let snap_resolution = ROTATE_SNAP_ANGLE.to_radians();
+				(angle / snap_resolution).round() * snap_resolution
+			}
+			RotationSnapMode::Level => {
+				let orientation = self.original_bound_transform.transform_vector2(DVec2::X).to_angle();
+				((orientation + angle) / FRAC_PI_2).round() * FRAC_PI_2 - orientation
+			}
+		}
+	}
+
+	/// The angle snap which `rotation_angle` applies for these positions, if angle snapping is enabled
+	pub fn angle_snap_event(&self, start: DVec2, current: DVec2, snap_angle: bool) -> Option<SnapEvent> {
+		snap_angle.then(|| SnapEvent::Angle(self.rotation_angle(start, current, true)))
+	}
+
+	/// Records the snap engaged by the latest update of a resize or rotate drag, such as from `grid_snap_event`, `angle_snap_event` or the snap manager's indicator.
+	/// Returns the snap only when it newly engages or changes to a different target, so feedback fires once each time a snap point is crossed rather than every frame.
+	/// Each newly engaged snap also records the current `transform` as a checkpoint.
+	pub fn update_engaged_snap(&mut self, snap: Option<SnapEvent>) -> Option<SnapEvent> {
+		let changed = snap != self.engaged_snap;
+		self.engaged_snap = snap;
+		let engaged = snap.filter(|_| changed);
+		if engaged.is_some() {
+			self.record_checkpoint();
+		}
+		engaged
+	}
+
+	/// Records the current `transform` as a checkpoint of the drag, which the tool can also call itself such as when a modifier key toggles a mode
+	pub fn record_checkpoint(&mut self) {
+		self.checkpoints.push(self.transform);
+	}
+
+	/// Rolls the cage back to the latest checkpoint of the drag, removing it so earlier ones are reached by rolling back again.
+	/// Returns the restored transform for the tool to transform the selection to, or `None` if there are no checkpoints left.
+	pub fn rollback_to_checkpoint(&mut self) -> Option<DAffine2> {
+		let checkpoint = self.checkpoints.pop()?;
+		self.transform = checkpoint;
+		self.engaged_snap = None;
+		Some(checkpoint)
+	}
+
+	/// Takes the checkpoints of the drag when it is committed, such as to keep them in the history, rather than letting them be cleared when the drag ends
+	pub fn take_checkpoints(&mut self) -> Vec<DAffine2> {
+		std::mem::take(&mut self.checkpoints)
+	}
+
+	/// The dimension of the current resize which a typed value targets
+	pub fn active_dimension(&self) -> Option<ResizeDimension> {
+		self.selected_edges.as_ref()?.active_dimension()
+	}
+
+	/// The position and size from `SelectedEdges::resize_to` for the typed `numeric_override`, which replaces the result of `new_size` until the resize is finalized
+	pub fn numeric_override_size(&self) -> Option<(DVec2, DVec2)> {
+		let selected_edges = self.selected_edges.as_ref()?;
+		selected_edges.resize_to(selected_edges.active_dimension()?, self.numeric_override?)
+	}
+
+	/// Clamps a rotation relative to the original orientation to the range between `min_angle` and `max_angle`
+	pub fn clamp_angle(&self, angle: f64) -> f64 {
+		let angle = self.min_angle.map_or(angle, |min_angle| angle.max(min_angle));
+		self.max_angle.map_or(angle, |max_angle| angle.min(max_angle))
+	}
+
+	/// Describes the operation that dragging from the cursor would perform, for use in tooltips, giving resizing priority over rotating like `get_cursor`.
+	/// This only looks at where the cursor is, so unlike the cursor shown it ignores the overrides from `set_handle_cursor` and the region remembered by `update_cursor`.
+	pub fn describe_hover(&self, cursor: DVec2, rotate: bool) -> Option<&'static str> {
+		if let Some(edges) = self.check_selected_edges(cursor) {
+			if edges.is_corner() {
+				Some("Resize width and height")
+			} else if edges.top || edges.bottom {
+				Some("Resize height")
+			} else {
+				Some("Resize width")
+			}
+		} else if rotate && self.check_rotate(cursor) {
+			Some("Rotate")
+		} else {
+			None
+		}
+	}
+
+	/// Maps edges of the bounds in their local space, as returned by `check_selected_edges` and used when resizing, to the edges they appear as in the viewport.
+	/// A view which flips the document, or a cage rotated by more than a quarter turn, draws the local top edge visually at the bottom (and likewise left at the right).
+	pub fn visual_edges(&self, edges: EdgeSelection) -> EdgeSelection {
+		let flip_x = self.transform.transform_vector2(DVec2::X).x < 0.;
+		let flip_y = self.transform.transform_vector2(DVec2::Y).y < 0.;
+		let EdgeSelection { top, bottom, left, right } = edges;
+		let (top, bottom) = if flip_y { (bottom, top) } else { (top, bottom) };
+		let (left, right) = if flip_x { (right, left) } else { (left, right) };
+		EdgeSelection { top, bottom, left, right }
+	}
+
+	/// Show a different cursor while hovering the handle at `index`, for tools where dragging that handle does something other than resizing
+	pub fn set_handle_cursor(&mut self, index: usize, cursor: MouseCursorIcon) {
+		if let Some(handle_cursor) = self.handle_cursors.get_mut(index) {
+			*handle_cursor = Some(cursor);
+		}
+	}
 
-		outside_bounds & inside_extended_bounds
+	/// Go back to the computed resize cursors on all handles
+	pub fn clear_handle_cursors(&mut self) {
+		self.handle_cursors = [None; 8];
 	}
 
 	/// Gets the required mouse cursor to show resizing bounds or optionally rotation
 	pub fn get_cursor(&self, input: &InputPreprocessorMessageHandler, rotate: bool) -> MouseCursorIcon {
-		if let Some(directions) = self.check_selected_edges(input.mouse.position) {
-			match directions {
-				(true, _, false, false) | (_, true, false, false) => MouseCursorIcon::NSResize,
-				(false, false, true, _) | (false, false, _, true) => MouseCursorIcon::EWResize,
-				(true, _, true, _) | (_, true, _, true) => MouseCursorIcon::NWSEResize,
-				(true, _, _, true) | (_, true, true, _) => MouseCursorIcon::NESWResize,
-				_ => MouseCursorIcon::Default,
-			}
-		} else if rotate && self.check_rotate(input.mouse.position) {
+		self.cursor_for_position(input.mouse.position, rotate)
+	}
+
+	/// The cursor shown for hovering the viewport space `position`, which `get_cursor` gives for the current mouse position
+	pub fn cursor_for_position(&self, position: DVec2, rotate: bool) -> MouseCursorIcon {
+		if let Some(edges) = self.check_selected_edges(position) {
+			self.resize_cursor(edges)
+		} else if rotate && self.check_rotate(position) {
 			MouseCursorIcon::Rotate
 		} else {
 			MouseCursorIcon::Default
 		}
 	}
+
+	/// Like `get_cursor`, but once the cursor is in the region for resizing or rotating, it stays there until the cursor is `cursor_hysteresis` past the boundary between them.
+	/// The region is remembered between calls, so this should be called on every pointer move while hovering.
+	pub fn update_cursor(&mut self, input: &InputPreprocessorMessageHandler, rotate: bool) -> MouseCursorIcon {
+		let cursor = input.mouse.position;
+		let center = self.to_screen((self.bounds[0] + self.bounds[1]) / 2.);
+		let inwards = (center - cursor).normalize_or_zero() * self.cursor_hysteresis;
+		let rotating_at = |position: DVec2| rotate && self.check_rotate(position) && self.check_selected_edges(position).is_none();
+
+		let region = match self.cursor_region {
+			Some(CursorRegion::Rotate) if rotating_at(cursor) || rotating_at(cursor - inwards) => Some((CursorRegion::Rotate, None)),
+			Some(CursorRegion::Resize) => self
+				.check_selected_edges(cursor)
+				.or_else(|| self.check_selected_edges(cursor + inwards))
+				.map(|edges| (CursorRegion::Resize, Some(edges))),
+			_ => None,
+		}
+		.or_else(|| self.check_selected_edges(cursor).map(|edges| (CursorRegion::Resize, Some(edges))))
+		.or_else(|| rotating_at(cursor).then_some((CursorRegion::Rotate, None)));
+
+		self.cursor_region = region.map(|(region, _)| region);
+		match region {
+			Some((CursorRegion::Resize, Some(edges))) => self.resize_cursor(edges),
+			Some((CursorRegion::Rotate, _)) => MouseCursorIcon::Rotate,
+			_ => MouseCursorIcon::Default,
+		}
+	}
+
+	/// The cursor for resizing with these edges, which is the override for the handle if one is set
+	fn resize_cursor(&self, edges: EdgeSelection) -> MouseCursorIcon {
+		if let Some(cursor) = edges.handle().and_then(|index| self.handle_cursors[index]) {
+			return cursor;
+		}
+		let edges = self.visual_edges(edges);
+		let EdgeSelection { top, bottom, left, right } = edges;
+		if edges.is_edge() {
+			if top || bottom {
+				MouseCursorIcon::NSResize
+			} else {
+				MouseCursorIcon::EWResize
+			}
+		} else if (top && left) || (bottom && right) {
+			MouseCursorIcon::NWSEResize
+		} else if (top && right) || (bottom && left) {
+			MouseCursorIcon::NESWResize
+		} else {
+			MouseCursorIcon::Default
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn handle_axes_follow_skewed_cage() {
+		// A skewed view, as drawn the handles become parallelograms whose sides run along these axes
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_cols_array(&[2., 0., 1., 1., 10., 10.]),
+			..Default::default()
+		};
+		let [x_axis, y_axis] = bounding_box.handle_axes();
+		assert!(x_axis.abs_diff_eq(DVec2::X, 1e-10));
+		assert!(y_axis.abs_diff_eq(DVec2::ONE.normalize(), 1e-10));
+		assert!(x_axis.dot(y_axis).abs() > 0.5, "The handle sides should not be perpendicular on a skewed cage");
+
+		let axis_aligned = BoundingBoxManager {
+			transform: DAffine2::from_scale(DVec2::new(3., 0.5)),
+			..Default::default()
+		};
+		assert_eq!(axis_aligned.handle_axes(), [DVec2::X, DVec2::Y]);
+	}
+
+	#[test]
+	fn edge_selection_corners_and_edges() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::IDENTITY,
+			..Default::default()
+		};
+
+		let corner = bounding_box.check_selected_edges(DVec2::new(0., 100.)).unwrap();
+		assert_eq!(
+			corner,
+			EdgeSelection {
+				bottom: true,
+				left: true,
+				..Default::default()
+			}
+		);
+		assert!(corner.is_corner() && !corner.is_edge());
+
+		let edge = bounding_box.check_selected_edges(DVec2::new(100., 50.)).unwrap();
+		assert_eq!(edge, EdgeSelection { right: true, ..Default::default() });
+		assert!(edge.is_edge() && !edge.is_corner());
+
+		assert_eq!(bounding_box.check_selected_edges(DVec2::splat(50.)), None);
+		assert_eq!(<(bool, bool, bool, bool)>::from(corner), (false, true, true, false));
+		assert_eq!(EdgeSelection::from((false, true, true, false)), corner);
+	}
+
+	#[test]
+	fn rotation_clamped_to_range() {
+		let bounding_box = BoundingBoxManager {
+			center_of_transformation: DVec2::ZERO,
+			min_angle: Some(-30_f64.to_radians()),
+			max_angle: Some(45_f64.to_radians()),
+			..Default::default()
+		};
+		let start = DVec2::X * 100.;
+		let at_angle = |degrees: f64| DVec2::from_angle(degrees.to_radians()) * 100.;
+
+		assert!((bounding_box.rotation_angle(start, at_angle(20.), false) - 20_f64.to_radians()).abs() < 1e-10);
+		assert!((bounding_box.rotation_angle(start, at_angle(90.), false) - 45_f64.to_radians()).abs() < 1e-10);
+		assert!((bounding_box.rotation_angle(start, at_angle(-90.), false) + 30_f64.to_radians()).abs() < 1e-10);
+		// Snapping happens before clamping, so a snapped angle beyond the limit is still clamped
+		assert!((bounding_box.rotation_angle(start, at_angle(55.), true) - 45_f64.to_radians()).abs() < 1e-10);
+	}
+
+	#[test]
+	fn snap_bounds_to_grid_axis_aligned() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(0.4, 1.6), DVec2::new(10.2, 19.5)],
+			transform: DAffine2::from_translation(DVec2::new(3., 4.)),
+			..Default::default()
+		};
+		bounding_box.snap_bounds_to_grid(1.);
+		assert_eq!(bounding_box.bounds, [DVec2::new(0., 2.), DVec2::new(10., 20.)]);
+		assert_eq!(bounding_box.transform, DAffine2::from_translation(DVec2::new(3., 4.)));
+
+		// Invalid steps leave the bounds untouched
+		bounding_box.snap_bounds_to_grid(0.);
+		assert_eq!(bounding_box.bounds, [DVec2::new(0., 2.), DVec2::new(10., 20.)]);
+	}
+
+	#[test]
+	fn snap_bounds_to_grid_rotated() {
+		let transform = DAffine2::from_angle(30_f64.to_radians());
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(-2., 3.), DVec2::new(48., 97.)],
+			transform,
+			..Default::default()
+		};
+		bounding_box.snap_bounds_to_grid(5.);
+		assert_eq!(bounding_box.bounds, [DVec2::new(0., 5.), DVec2::new(50., 95.)]);
+		assert_eq!(bounding_box.transform, transform);
+	}
+
+	#[test]
+	fn describe_hover_labels() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::IDENTITY,
+			..Default::default()
+		};
+
+		assert_eq!(bounding_box.describe_hover(DVec2::new(50., 0.), true), Some("Resize height"));
+		assert_eq!(bounding_box.describe_hover(DVec2::new(0., 50.), true), Some("Resize width"));
+		assert_eq!(bounding_box.describe_hover(DVec2::new(100., 100.), true), Some("Resize width and height"));
+		assert_eq!(bounding_box.describe_hover(DVec2::new(-15., -15.), true), Some("Rotate"));
+		assert_eq!(bounding_box.describe_hover(DVec2::new(-15., -15.), false), None);
+		assert_eq!(bounding_box.describe_hover(DVec2::splat(50.), true), None);
+	}
+
+	#[test]
+	fn resize_corner_about_center_anchor() {
+		let bounds = [DVec2::ZERO, DVec2::splat(100.)];
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, bounds);
+		let (min, size) = selected_edges.new_size(DVec2::splat(150.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!((min, size), (DVec2::ZERO, DVec2::splat(150.)));
+
+		selected_edges.set_anchor(PivotPosition::Center);
+		let (min, size) = selected_edges.new_size(DVec2::splat(150.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!((min, size), (DVec2::splat(-50.), DVec2::splat(200.)));
+
+		// Dragging towards the anchor shrinks both sides equally
+		let (min, size) = selected_edges.new_size(DVec2::splat(75.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!((min, size), (DVec2::splat(25.), DVec2::splat(50.)));
+	}
+
+	#[test]
+	fn debug_lines_describe_state() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(20., 10.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::splat(2.), 90_f64.to_radians(), DVec2::new(5., 6.)),
+			..Default::default()
+		};
+		bounding_box.selected_edges = Some(SelectedEdges::new(true, false, false, true, bounding_box.bounds));
+		let transform = bounding_box.transform;
+
+		let lines = bounding_box.debug_lines();
+		assert_eq!(lines[0], "Bounds: [0.00, 0.00] to [20.00, 10.00]");
+		assert_eq!(lines[1], "Rotation: 90.00°  Scale: [2.000, 2.000]");
+		assert_eq!(lines[2], "Translation: [5.00, 6.00]");
+		assert_eq!(lines[5], "Edges: top, right");
+		assert_eq!(bounding_box.transform, transform);
+	}
+
+	#[test]
+	fn proportional_handles_scale_threshold() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::from_scale(DVec2::splat(2.)),
+			..Default::default()
+		};
+		// 15 pixels inside the left edge on screen
+		let cursor = DVec2::new(15., 100.);
+
+		assert_eq!(bounding_box.handle_scale(), 1.);
+		assert_eq!(bounding_box.check_selected_edges(cursor), None);
+
+		bounding_box.handle_scale_mode = HandleScaleMode::ProportionalToObject;
+		assert_eq!(bounding_box.handle_scale(), 2.);
+		assert_eq!(bounding_box.check_selected_edges(cursor), Some(EdgeSelection { left: true, ..Default::default() }));
+	}
+
+	#[test]
+	fn lerp_between_cages() {
+		let start = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::new(1., 2.), 170_f64.to_radians(), DVec2::new(10., 20.)),
+			..Default::default()
+		};
+		let end = BoundingBoxManager {
+			bounds: [DVec2::new(-10., 0.), DVec2::new(200., 80.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::new(3., 1.), -170_f64.to_radians(), DVec2::new(-40., 60.)),
+			..Default::default()
+		};
+
+		let at_start = start.lerp(&end, 0.);
+		assert_eq!(at_start.bounds, start.bounds);
+		assert!(at_start.transform.abs_diff_eq(start.transform, 1e-10));
+
+		let at_end = start.lerp(&end, 1.);
+		assert_eq!(at_end.bounds, end.bounds);
+		assert!(at_end.transform.abs_diff_eq(end.transform, 1e-10));
+
+		// Out of range values are clamped
+		assert!(start.lerp(&end, 2.).transform.abs_diff_eq(end.transform, 1e-10));
+
+		// The rotation takes the shortest path through 180° rather than sweeping back through 0°
+		let (_, angle, _) = start.lerp(&end, 0.5).transform.to_scale_angle_translation();
+		assert!((angle.abs() - PI).abs() < 1e-10);
+	}
+
+	#[test]
+	fn translate_axis_locked() {
+		let bounding_box = BoundingBoxManager::default();
+		let delta = DVec2::new(10., 3.);
+
+		assert_eq!(bounding_box.translate(delta, false), DAffine2::from_translation(delta));
+
+		let locked = bounding_box.translate(delta, true).translation;
+		assert!(locked.y.abs() < 1e-10);
+		assert!((locked.x - delta.length()).abs() < 1e-10);
+
+		let locked = bounding_box.translate(DVec2::new(-2., -30.), true).translation;
+		assert!(locked.x.abs() < 1e-10);
+		assert!(locked.y < 0.);
+	}
+
+	#[test]
+	fn exterior_handles() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::IDENTITY,
+			..Default::default()
+		};
+		let outside_left = DVec2::new(-10., 50.);
+		let inside_left = DVec2::new(5., 50.);
+
+		assert_eq!(bounding_box.displayed_handle_positions(), bounding_box.evaluate_transform_handle_positions());
+		assert_eq!(bounding_box.check_selected_edges(outside_left), None);
+		assert!(bounding_box.check_selected_edges(inside_left).is_some());
+
+		bounding_box.exterior_handle_offset = 10.;
+		let positions = bounding_box.displayed_handle_positions();
+		assert_eq!(positions[0], DVec2::splat(-10.));
+		assert_eq!(positions[1], DVec2::new(-10., 50.));
+		assert_eq!(positions[7], DVec2::splat(110.));
+		assert_eq!(bounding_box.check_selected_edges(outside_left), Some(EdgeSelection { left: true, ..Default::default() }));
+		assert_eq!(bounding_box.check_selected_edges(inside_left), None);
+	}
+
+	#[test]
+	fn rotate_corner_and_edge_regions() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::IDENTITY,
+			..Default::default()
+		};
+		let corner = DVec2::new(-15., -15.);
+		let beside_corner = DVec2::new(110., 5.);
+		let edge = DVec2::new(50., 115.);
+
+		assert_eq!(bounding_box.check_rotate_region(corner), Some(RotateRegion::Corner));
+		assert_eq!(bounding_box.check_rotate_region(beside_corner), Some(RotateRegion::Corner));
+		assert_eq!(bounding_box.check_rotate_region(edge), Some(RotateRegion::Edge));
+		assert_eq!(bounding_box.check_rotate_region(DVec2::splat(50.)), None);
+		assert_eq!(bounding_box.check_rotate_region(DVec2::splat(150.)), None);
+		assert!(bounding_box.check_rotate(edge));
+
+		bounding_box.corner_only_rotation = true;
+		assert!(bounding_box.check_rotate(corner));
+		assert!(!bounding_box.check_rotate(edge));
+		assert_eq!(bounding_box.describe_hover(edge, true), None);
+	}
+
+	#[test]
+	#[cfg(feature = "tool-state-serde")]
+	fn serialization_round_trip() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(-3., 4.), DVec2::new(50., 60.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::new(2., 1.), 0.3, DVec2::new(7., -8.)),
+			center_of_transformation: DVec2::new(20., 30.),
+			handle_scale_mode: HandleScaleMode::ProportionalToObject,
+			..Default::default()
+		};
+		let mut selected_edges = SelectedEdges::new(true, false, true, false, bounding_box.bounds);
+		selected_edges.set_anchor(PivotPosition::Center);
+		bounding_box.selected_edges = Some(selected_edges);
+
+		let serialized = serde_json::to_string(&bounding_box).unwrap();
+		let deserialized: BoundingBoxManager = serde_json::from_str(&serialized).unwrap();
+		assert_eq!(deserialized, bounding_box);
+	}
+
+	#[test]
+	fn corner_hits_on_rotated_cage() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::from_angle_translation(30_f64.to_radians(), DVec2::new(200., 50.)),
+			..Default::default()
+		};
+		let bottom_right = bounding_box.evaluate_transform_handle_positions()[7];
+		let bottom_right_corner = EdgeSelection {
+			bottom: true,
+			right: true,
+			..Default::default()
+		};
+		// An offset along the local axes of the cage, converted to the rotated viewport space
+		let offset = |x: f64, y: f64| bottom_right + bounding_box.transform.transform_vector2(DVec2::new(x, y));
+
+		assert_eq!(bounding_box.check_selected_edges(bottom_right), Some(bottom_right_corner));
+		assert_eq!(bounding_box.check_selected_edges(offset(5., 5.)), Some(bottom_right_corner));
+		// Inside the edge bands on both axes but further than the handle radius from the corner, so only the nearer edge is selected
+		assert_eq!(bounding_box.check_selected_edges(offset(9., 6.)), Some(EdgeSelection { bottom: true, ..Default::default() }));
+		assert_eq!(bounding_box.check_selected_edges(offset(-40., 3.)), Some(EdgeSelection { bottom: true, ..Default::default() }));
+		assert_eq!(bounding_box.check_selected_edges(offset(-40., -40.)), None);
+	}
+
+	#[test]
+	fn expand_bounds_to_include_point() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(10.)],
+			transform: DAffine2::IDENTITY,
+			..Default::default()
+		};
+		bounding_box.expand_to_include(DVec2::new(5., 5.));
+		assert_eq!(bounding_box.bounds, [DVec2::ZERO, DVec2::splat(10.)]);
+		bounding_box.expand_to_include(DVec2::new(-5., 20.));
+		assert_eq!(bounding_box.bounds, [DVec2::new(-5., 0.), DVec2::new(10., 20.)]);
+
+		// On a cage rotated by 90°, the local x axis points down the viewport and the local y axis points left
+		let transform = DAffine2::from_angle(90_f64.to_radians());
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(10.)],
+			transform,
+			..Default::default()
+		};
+		bounding_box.expand_to_include(DVec2::new(5., 30.));
+		assert!(bounding_box.bounds[0].abs_diff_eq(DVec2::new(0., -5.), 1e-10));
+		assert!(bounding_box.bounds[1].abs_diff_eq(DVec2::new(30., 10.), 1e-10));
+		bounding_box.expand_to_include(DVec2::new(20., 5.));
+		assert!(bounding_box.bounds[0].abs_diff_eq(DVec2::new(0., -20.), 1e-10));
+		assert_eq!(bounding_box.transform, transform);
+	}
+
+	#[test]
+	fn rotation_compass_marks_original_orientation() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::from_angle(90_f64.to_radians()),
+			center_of_transformation: DVec2::new(10., 20.),
+			applied_rotation: Some(90_f64.to_radians()),
+			rotating: true,
+			rotation_compass_radius: 40.,
+			..Default::default()
+		};
+		let transform = bounding_box.transform;
+		assert!(bounding_box.rotation_compass().is_none());
+
+		bounding_box.show_rotation_compass = true;
+		let (ticks, needle) = bounding_box.rotation_compass().unwrap();
+		// The original orientation pointed along the viewport x axis, before being rotated by 90°
+		assert!(ticks[0][0].abs_diff_eq(DVec2::new(10., 20.), 1e-10));
+		assert!(ticks[0][1].abs_diff_eq(DVec2::new(50., 20.), 1e-10));
+		assert!(ticks[1][1].abs_diff_eq(DVec2::new(10., 60.), 1e-10));
+		assert!(needle[1].abs_diff_eq(DVec2::new(10., 60.), 1e-10));
+		assert_eq!(bounding_box.transform, transform);
+
+		bounding_box.applied_rotation = None;
+		assert!(bounding_box.rotation_compass().is_none());
+	}
+
+	#[test]
+	fn aabb_of_rotated_cage() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::from_angle_translation(45_f64.to_radians(), DVec2::new(10., 0.)),
+			..Default::default()
+		};
+		let half_diagonal = 100. / 2_f64.sqrt();
+
+		let corners = bounding_box.corners();
+		assert!(corners[0].abs_diff_eq(DVec2::new(10., 0.), 1e-10));
+		assert!(corners[2].abs_diff_eq(DVec2::new(10., 2. * half_diagonal), 1e-10));
+
+		let [min, max] = bounding_box.aabb();
+		assert!(min.abs_diff_eq(DVec2::new(10. - half_diagonal, 0.), 1e-10));
+		assert!(max.abs_diff_eq(DVec2::new(10. + half_diagonal, 2. * half_diagonal), 1e-10));
+		assert!(max.x - min.x > bounding_box.bounds[1].x - bounding_box.bounds[0].x);
+	}
+
+	#[test]
+	fn resize_snaps_to_offset_grid() {
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::new(3., 3.), DVec2::new(33., 33.)]);
+		selected_edges.set_grid(Some(ResizeGrid { origin: DVec2::splat(3.), step: 10. }));
+
+		let (min, size) = selected_edges.new_size(DVec2::new(47., 51.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(min, DVec2::splat(3.));
+		assert_eq!(min + size, DVec2::new(43., 53.));
+
+		// The default grid is the pixel grid at the origin
+		selected_edges.set_grid(Some(ResizeGrid::default()));
+		let (min, size) = selected_edges.new_size(DVec2::new(47.4, 51.6), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(min + size, DVec2::new(47., 52.));
+	}
+
+	#[test]
+	fn all_edges_expand_symmetrically() {
+		let selected_edges = SelectedEdges::all_edges([DVec2::ZERO, DVec2::new(100., 60.)]);
+		assert!(selected_edges.is_all_edges());
+		assert_eq!(selected_edges.calculate_pivot(), DVec2::new(50., 30.));
+
+		// Dragging 20 units to the right of the frame moves every edge out by 20
+		let (min, size) = selected_edges.new_size(DVec2::new(120., 30.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!((min, size), (DVec2::splat(-20.), DVec2::new(140., 100.)));
+
+		// Dragging 10 units inside the bottom edge moves every edge in by 10
+		let (min, size) = selected_edges.new_size(DVec2::new(50., 50.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!((min, size), (DVec2::splat(10.), DVec2::new(80., 40.)));
+
+		// The frame collapses to a line rather than inverting
+		let (_, size) = selected_edges.new_size(DVec2::new(50., 30.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(40., 0.));
+	}
+
+	#[test]
+	fn snap_events_fire_when_engaged() {
+		let mut bounding_box = BoundingBoxManager::default();
+		let start = DVec2::X * 100.;
+		let at_angle = |degrees: f64| DVec2::from_angle(degrees.to_radians()) * 100.;
+
+		assert_eq!(bounding_box.angle_snap_event(start, at_angle(20.), false), None);
+		let snap = bounding_box.angle_snap_event(start, at_angle(20.), true);
+		assert_eq!(bounding_box.update_engaged_snap(snap), Some(SnapEvent::Angle(15_f64.to_radians())));
+		// Moving within the same snap doesn't fire again
+		let snap = bounding_box.angle_snap_event(start, at_angle(17.), true);
+		assert_eq!(bounding_box.update_engaged_snap(snap), None);
+		// Crossing into the next snap fires
+		let snap = bounding_box.angle_snap_event(start, at_angle(25.), true);
+		assert_eq!(bounding_box.update_engaged_snap(snap), Some(SnapEvent::Angle(30_f64.to_radians())));
+		// Releasing the snap doesn't fire, but engaging the same one again does
+		assert_eq!(bounding_box.update_engaged_snap(None), None);
+		assert_eq!(bounding_box.update_engaged_snap(snap), snap);
+
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::splat(10.)]);
+		assert_eq!(selected_edges.grid_snap_event(DVec2::splat(12.3), DAffine2::IDENTITY), None);
+		selected_edges.set_grid(Some(ResizeGrid { origin: DVec2::ZERO, step: 5. }));
+		assert_eq!(selected_edges.grid_snap_event(DVec2::splat(12.3), DAffine2::IDENTITY), Some(SnapEvent::Grid(DVec2::splat(10.))));
+	}
+
+	#[test]
+	fn resize_locked_to_diagonal() {
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::new(10., 20.)]);
+		selected_edges.set_lock_to_diagonal(true);
+
+		// The mouse is projected onto the line from the top left pivot through the bottom right corner
+		let (min, size) = selected_edges.new_size(DVec2::new(20., 20.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(min, DVec2::ZERO);
+		assert!(size.abs_diff_eq(DVec2::new(12., 24.), 1e-9), "{size}");
+
+		// Edge drags are unaffected
+		let selected_edges = SelectedEdges::new(false, false, false, true, [DVec2::ZERO, DVec2::new(10., 20.)]);
+		let (_, size) = selected_edges.new_size(DVec2::new(20., 5.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(20., 20.));
+
+		// A degenerate diagonal leaves the mouse as it is
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::ZERO]);
+		selected_edges.set_lock_to_diagonal(true);
+		let (_, size) = selected_edges.new_size(DVec2::new(3., 4.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(3., 4.));
+	}
+
+	#[test]
+	fn center_around_default_is_symmetric() {
+		let selected_edges = SelectedEdges::new(false, false, false, true, [DVec2::new(10., 0.), DVec2::new(30., 40.)]);
+		assert_eq!(selected_edges.default_center_around(), DVec2::new(20., 20.));
+
+		// Dragging the right edge out by 10 moves the left edge out by 10 too
+		let center = Some(selected_edges.default_center_around());
+		let (min, size) = selected_edges.new_size(DVec2::new(40., 20.), DAffine2::IDENTITY, center, false, None);
+		assert_eq!((min, size), (DVec2::new(0., 0.), DVec2::new(40., 40.)));
+	}
+
+	#[test]
+	fn fixed_area_resize() {
+		let mut selected_edges = SelectedEdges::new(false, false, false, true, [DVec2::ZERO, DVec2::new(20., 10.)]);
+		selected_edges.set_fixed_area(true);
+
+		// Doubling the width halves the height about its center
+		let (min, size) = selected_edges.new_size(DVec2::new(40., 3.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!((min, size), (DVec2::new(0., 2.5), DVec2::new(40., 5.)));
+		assert!((size.x * size.y - 200.).abs() < 1e-9);
+
+		// Shrinking the height widens the bounds
+		let mut selected_edges = SelectedEdges::new(true, false, false, false, [DVec2::ZERO, DVec2::new(20., 10.)]);
+		selected_edges.set_fixed_area(true);
+		let (_, size) = selected_edges.new_size(DVec2::new(7., 6.), DAffine2::IDENTITY, None, false, None);
+		assert!((size.x * size.y - 200.).abs() < 1e-9, "{size}");
+
+		// Zero area bounds ignore the constraint
+		let mut selected_edges = SelectedEdges::new(false, false, false, true, [DVec2::ZERO, DVec2::new(20., 0.)]);
+		selected_edges.set_fixed_area(true);
+		let (_, size) = selected_edges.new_size(DVec2::new(40., 0.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(40., 0.));
+	}
+
+	#[test]
+	fn flipped_view_edges_and_cursors() {
+		// A y-up view, where the local top edge of the bounds is drawn at the bottom
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_translation(DVec2::new(0., 200.)) * DAffine2::from_scale(DVec2::new(1., -1.)),
+			..Default::default()
+		};
+		let mut input = InputPreprocessorMessageHandler::default();
+		let cursor = |input: &mut InputPreprocessorMessageHandler, position: DVec2| {
+			input.mouse.position = position;
+			bounding_box.get_cursor(input, false)
+		};
+
+		// The visually top edge is the local bottom edge
+		let edges = bounding_box.check_selected_edges(DVec2::new(50., 150.)).unwrap();
+		assert_eq!(edges, EdgeSelection { bottom: true, ..Default::default() });
+		assert_eq!(bounding_box.visual_edges(edges), EdgeSelection { top: true, ..Default::default() });
+		assert_eq!(cursor(&mut input, DVec2::new(50., 150.)), MouseCursorIcon::NSResize);
+		assert_eq!(cursor(&mut input, DVec2::new(100., 175.)), MouseCursorIcon::EWResize);
+
+		// The visually top left corner is the local bottom left corner
+		let edges = bounding_box.check_selected_edges(DVec2::new(0., 150.)).unwrap();
+		assert_eq!(
+			bounding_box.visual_edges(edges),
+			EdgeSelection {
+				top: true,
+				left: true,
+				..Default::default()
+			}
+		);
+		assert_eq!(cursor(&mut input, DVec2::new(0., 150.)), MouseCursorIcon::NWSEResize);
+		assert_eq!(cursor(&mut input, DVec2::new(100., 150.)), MouseCursorIcon::NESWResize);
+	}
+
+	#[test]
+	fn numeric_override_resize() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut bounding_box = BoundingBoxManager { bounds, ..Default::default() };
+		bounding_box.numeric_override = Some(80.);
+		assert_eq!(bounding_box.numeric_override_size(), None);
+
+		// The left edge targets the width, keeping the right edge fixed
+		bounding_box.selected_edges = Some(SelectedEdges::new(false, false, true, false, bounds));
+		assert_eq!(bounding_box.active_dimension(), Some(ResizeDimension::Width));
+		assert_eq!(bounding_box.numeric_override_size(), Some((DVec2::new(20., 0.), DVec2::new(80., 50.))));
+
+		// The bottom edge targets the height, keeping the top edge fixed
+		bounding_box.selected_edges = Some(SelectedEdges::new(false, true, false, false, bounds));
+		assert_eq!(bounding_box.active_dimension(), Some(ResizeDimension::Height));
+		assert_eq!(bounding_box.numeric_override_size(), Some((DVec2::ZERO, DVec2::new(100., 80.))));
+
+		// Corners target the width and scale the height to match
+		bounding_box.selected_edges = Some(SelectedEdges::new(true, false, false, true, bounds));
+		assert_eq!(bounding_box.active_dimension(), Some(ResizeDimension::Width));
+		assert_eq!(bounding_box.numeric_override_size(), Some((DVec2::new(0., 10.), DVec2::new(80., 40.))));
+
+		// All edges resize about the center
+		bounding_box.selected_edges = Some(SelectedEdges::all_edges(bounds));
+		assert_eq!(bounding_box.numeric_override_size(), Some((DVec2::new(10., 5.), DVec2::new(80., 40.))));
+
+		let selected_edges = SelectedEdges::new(false, true, false, false, bounds);
+		assert_eq!(selected_edges.resize_to(ResizeDimension::Width, 10.), None);
+		assert_eq!(selected_edges.resize_to(ResizeDimension::Height, -10.), None);
+	}
+
+	#[test]
+	fn handle_halo_passes() {
+		let mut bounding_box = BoundingBoxManager::default();
+		assert_eq!(bounding_box.handle_passes(), vec![(6., None)]);
+
+		bounding_box.handle_halo = true;
+		assert_eq!(
+			bounding_box.handle_passes(),
+			vec![(6. + BOUNDS_HANDLE_HALO_WIDTH * 2., Some(COLOR_OVERLAY_SNAP_BACKGROUND)), (6., None)]
+		);
+	}
+
+	#[test]
+	fn normalize_swapped_bounds() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(100., 0.), DVec2::new(0., 50.)],
+			..Default::default()
+		};
+		assert!(!bounding_box.is_normalized());
+
+		bounding_box.normalize_bounds();
+		assert!(bounding_box.is_normalized());
+		assert_eq!(bounding_box.bounds, [DVec2::ZERO, DVec2::new(100., 50.)]);
+
+		bounding_box.bounds = [DVec2::splat(50.), DVec2::ZERO];
+		bounding_box.normalize_bounds();
+		assert_eq!(bounding_box.bounds, [DVec2::ZERO, DVec2::splat(50.)]);
+		bounding_box.normalize_bounds();
+		assert_eq!(bounding_box.bounds, [DVec2::ZERO, DVec2::splat(50.)]);
+	}
+
+	#[test]
+	fn level_rotation_snap() {
+		let mut bounding_box = BoundingBoxManager {
+			original_bound_transform: DAffine2::from_angle(80_f64.to_radians()),
+			..Default::default()
+		};
+		assert!((bounding_box.snap_rotation_angle(7_f64.to_radians()) - 0.).abs() < 1e-10);
+
+		// A cage at 80° is brought to 90°, whichever way it has been dragged
+		bounding_box.rotation_snap_mode = RotationSnapMode::Level;
+		assert!((bounding_box.snap_rotation_angle(5_f64.to_radians()) - 10_f64.to_radians()).abs() < 1e-10);
+		assert!((bounding_box.snap_rotation_angle(-20_f64.to_radians()) - 10_f64.to_radians()).abs() < 1e-10);
+		// Or on to the next quarter turn
+		assert!((bounding_box.snap_rotation_angle(60_f64.to_radians()) - 100_f64.to_radians()).abs() < 1e-10);
+
+		let start = DVec2::X * 100.;
+		let current = DVec2::from_angle(3_f64.to_radians()) * 100.;
+		assert!((bounding_box.rotation_angle(start, current, true) - 10_f64.to_radians()).abs() < 1e-10);
+		assert!((bounding_box.rotation_angle(start, current, false) - 3_f64.to_radians()).abs() < 1e-10);
+	}
+
+	#[test]
+	fn handle_positions_for_candidate_bounds() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::splat(2.), 30_f64.to_radians(), DVec2::new(5., 6.)),
+			..Default::default()
+		};
+		assert_eq!(bounding_box.handle_positions_for(bounding_box.bounds), bounding_box.evaluate_transform_handle_positions());
+
+		let candidate = [DVec2::ZERO, DVec2::new(50., 50.)];
+		let positions = bounding_box.handle_positions_for(candidate);
+		assert_eq!(positions[0], bounding_box.transform.transform_point2(DVec2::ZERO));
+		assert_eq!(positions[7], bounding_box.transform.transform_point2(DVec2::new(50., 50.)));
+		assert_eq!(bounding_box.bounds[1], DVec2::new(100., 50.));
+	}
+
+	#[test]
+	fn rounded_rotate_band_corners() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			..Default::default()
+		};
+		// 15 pixels out on both axes is within the rectangular band but about 21 pixels from the corner itself
+		let diagonal = DVec2::splat(-15.);
+		let near_diagonal = DVec2::splat(-12.);
+		let along_edge = DVec2::new(50., -15.);
+		assert_eq!(bounding_box.check_rotate_region(diagonal), Some(RotateRegion::Corner));
+
+		bounding_box.rounded_rotate_band = true;
+		assert_eq!(bounding_box.check_rotate_region(diagonal), None);
+		assert_eq!(bounding_box.check_rotate_region(near_diagonal), Some(RotateRegion::Corner));
+		assert_eq!(bounding_box.check_rotate_region(along_edge), Some(RotateRegion::Edge));
+	}
+
+	#[test]
+	fn numpad_pivot_anchors() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_translation(DVec2::new(10., 20.)),
+			..Default::default()
+		};
+		let expected = [
+			(1, DVec2::new(10., 70.)),
+			(2, DVec2::new(60., 70.)),
+			(3, DVec2::new(110., 70.)),
+			(4, DVec2::new(10., 45.)),
+			(5, DVec2::new(60., 45.)),
+			(6, DVec2::new(110., 45.)),
+			(7, DVec2::new(10., 20.)),
+			(8, DVec2::new(60., 20.)),
+			(9, DVec2::new(110., 20.)),
+		];
+		for (digit, pivot) in expected {
+			let anchor = numpad_pivot_position(digit).unwrap();
+			assert_eq!(bounding_box.set_pivot_anchor(anchor), Some(pivot), "Digit {digit}");
+			assert_eq!(bounding_box.center_of_transformation, pivot);
+		}
+		assert_eq!(numpad_pivot_position(0), None);
+		assert_eq!(bounding_box.set_pivot_anchor(PivotPosition::None), None);
+		assert_eq!(bounding_box.center_of_transformation, DVec2::new(110., 20.));
+
+		// The anchor of a resize in progress follows along
+		bounding_box.selected_edges = Some(SelectedEdges::new(false, false, false, true, bounding_box.bounds));
+		bounding_box.set_pivot_anchor(PivotPosition::Center);
+		let (min, size) = bounding_box.selected_edges.as_ref().unwrap().new_size(DVec2::new(160., 45.), bounding_box.transform, None, false, None);
+		assert_eq!((min, size), (DVec2::new(-50., 0.), DVec2::new(200., 50.)));
+	}
+
+	#[test]
+	fn drag_delta_transform_isolates_change() {
+		let transform = DAffine2::from_scale_angle_translation(DVec2::new(2., 3.), 0.3, DVec2::new(5., 6.));
+		let mut bounding_box = BoundingBoxManager {
+			transform,
+			original_bound_transform: transform,
+			..Default::default()
+		};
+		assert!(bounding_box.drag_delta_transform().abs_diff_eq(DAffine2::IDENTITY, 1e-10));
+
+		let delta = DAffine2::from_angle(0.5) * DAffine2::from_translation(DVec2::new(10., -4.));
+		bounding_box.transform = delta * transform;
+		assert!(bounding_box.drag_delta_transform().abs_diff_eq(delta, 1e-10));
+	}
+
+	#[test]
+	fn angle_guide_persists_after_snap() {
+		let mut bounding_box = BoundingBoxManager {
+			original_bound_transform: DAffine2::from_angle(10_f64.to_radians()),
+			center_of_transformation: DVec2::new(50., 50.),
+			applied_rotation: Some(20_f64.to_radians()),
+			..Default::default()
+		};
+		bounding_box.update_angle_guide(true);
+		assert_eq!(bounding_box.angle_guide_line(), None, "The guide is off by default");
+
+		bounding_box.show_angle_guide = true;
+		bounding_box.update_angle_guide(true);
+		let (pivot, angle) = bounding_box.angle_guide.unwrap();
+		assert_eq!(pivot, DVec2::new(50., 50.));
+		assert!((angle - 30_f64.to_radians()).abs() < 1e-10);
+
+		// Releasing the drag keeps the guide
+		bounding_box.applied_rotation = None;
+		let [start, end] = bounding_box.angle_guide_line().unwrap();
+		assert!((start.lerp(end, 0.5) - pivot).length() < 1e-6);
+		assert!(((end - start).to_angle() - angle).abs() < 1e-10);
+
+		bounding_box.clear_angle_guide();
+		assert_eq!(bounding_box.angle_guide_line(), None);
+	}
+
+	#[test]
+	fn symmetric_scaling_about_centroid() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut bounding_box = BoundingBoxManager { bounds, ..Default::default() };
+		assert_eq!(bounding_box.center_around(), DVec2::new(50., 25.));
+
+		bounding_box.set_centroid_from([DVec2::new(10., 10.), DVec2::new(30., 20.)]);
+		assert_eq!(bounding_box.centroid, Some(DVec2::new(20., 15.)));
+		assert_eq!(bounding_box.center_around(), DVec2::new(20., 15.));
+
+		// Dragging the right edge out by 20 moves the left edge out by the same distance beyond the centroid, which is mirrored across it
+		let selected_edges = SelectedEdges::new(false, false, false, true, bounds);
+		let (min, size) = selected_edges.new_size(DVec2::new(120., 25.), DAffine2::IDENTITY, Some(bounding_box.center_around()), false, None);
+		assert_eq!((min, size), (DVec2::new(-80., 0.), DVec2::new(200., 50.)));
+
+		bounding_box.set_centroid_from([]);
+		assert_eq!(bounding_box.center_around(), DVec2::new(50., 25.));
+	}
+
+	#[test]
+	fn anisotropic_grab_thresholds() {
+		// A 2:1 view, where 10 pixels on screen spans 5 units horizontally and 10 units vertically
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: DAffine2::from_scale(DVec2::new(2., 1.)),
+			..Default::default()
+		};
+		assert_eq!(bounding_box.compute_viewport_threshold(10.), [5., 10.]);
+
+		// Vertical and horizontal edges are grabbed from the same distance on screen
+		let left = EdgeSelection { left: true, ..Default::default() };
+		let top = EdgeSelection { top: true, ..Default::default() };
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(9., 50.)), Some(left));
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(-9., 50.)), Some(left));
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(100., 9.)), Some(top));
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(100., -9.)), Some(top));
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(11., 50.)), None);
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(100., 11.)), None);
+	}
+
+	#[test]
+	fn rotation_dead_zone_near_pivot() {
+		let mut bounding_box = BoundingBoxManager {
+			center_of_transformation: DVec2::new(50., 50.),
+			rotation_dead_zone: 8.,
+			..Default::default()
+		};
+		let start = DVec2::new(150., 50.);
+
+		// Passing right next to the pivot doesn't rotate, even though the angle to the cursor changes wildly there
+		assert_eq!(bounding_box.rotation_angle(start, DVec2::new(50., 55.), false), 0.);
+		assert_eq!(bounding_box.rotation_angle(start, DVec2::new(45., 50.), false), 0.);
+
+		// Outside of it the rotation is applied, and then kept when re-entering
+		let angle = bounding_box.rotation_angle(start, DVec2::new(50., 150.), false);
+		assert!((angle - FRAC_PI_2).abs() < 1e-10);
+		bounding_box.applied_rotation = Some(angle);
+		assert_eq!(bounding_box.rotation_angle(start, DVec2::new(52., 48.), false), angle);
+	}
+
+	#[test]
+	fn svg_transform_string() {
+		let mut bounding_box = BoundingBoxManager::default();
+		assert_eq!(bounding_box.to_svg_transform(), "matrix(1,0,0,1,0,0)");
+
+		bounding_box.transform = DAffine2::from_scale_angle_translation(DVec2::new(2., 3.), 90_f64.to_radians(), DVec2::new(10., -20.5));
+		assert_eq!(bounding_box.to_svg_transform(), "matrix(0,2,-3,0,10,-20.5)");
+	}
+
+	/// Records what is drawn instead of drawing it
+	#[derive(Default)]
+	struct RecordingOverlay {
+		quads: Vec<[DVec2; 4]>,
+		rounded_quads: Vec<([DVec2; 4], f64)>,
+		squares: Vec<(DVec2, Option<f64>)>,
+		square_fills: Vec<Option<String>>,
+		lines: Vec<[DVec2; 2]>,
+		circles: Vec<DVec2>,
+		diamonds: Vec<DVec2>,
+		texts: Vec<String>,
+	}
+
+	impl RecordingOverlay {
+		fn is_empty(&self) -> bool {
+			self.quads.is_empty() && self.rounded_quads.is_empty() && self.squares.is_empty() && self.lines.is_empty() && self.circles.is_empty() && self.diamonds.is_empty() && self.texts.is_empty()
+		}
+	}
+
+	impl OverlayDraw for RecordingOverlay {
+		fn quad(&mut self, quad: Quad, _color_fill: Option<&str>) {
+			self.quads.push(quad.0);
+		}
+		fn rounded_quad(&mut self, quad: Quad, radius: f64, _color_fill: Option<&str>) {
+			self.rounded_quads.push((quad.0, radius));
+		}
+		fn dashed_line(&mut self, start: DVec2, end: DVec2, _color: Option<&str>, _dash_width: Option<f64>) {
+			self.lines.push([start, end]);
+		}
+		fn square(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, _color_stroke: Option<&str>) {
+			self.squares.push((position, size));
+			self.square_fills.push(color_fill.map(str::to_string));
+		}
+		fn oriented_square(&mut self, position: DVec2, size: Option<f64>, _axes: [DVec2; 2], color_fill: Option<&str>, _color_stroke: Option<&str>) {
+			self.squares.push((position, size));
+			self.square_fills.push(color_fill.map(str::to_string));
+		}
+		fn diamond(&mut self, position: DVec2, _size: Option<f64>, _color_fill: Option<&str>, _color_stroke: Option<&str>) {
+			self.diamonds.push(position);
+		}
+		fn circle(&mut self, position: DVec2, _radius: f64, _color_fill: Option<&str>, _color_stroke: Option<&str>) {
+			self.circles.push(position);
+		}
+		fn text(&mut self, text: &str, _font_color: &str, _background_color: Option<&str>, _transform: DAffine2, _padding: f64, _pivot: [Pivot; 2]) {
+			self.texts.push(text.to_string());
+		}
+	}
+
+	#[test]
+	fn render_overlays_draws_cage_and_handles() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_angle(0.3),
+			..Default::default()
+		};
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.quads, vec![bounding_box.corners()]);
+		let positions: Vec<_> = overlay.squares.iter().map(|&(position, _)| position).collect();
+		assert_eq!(positions, bounding_box.displayed_handle_positions().to_vec());
+		assert!(overlay.squares.iter().all(|&(_, size)| size == Some(6.)));
+		assert!(overlay.lines.is_empty() && overlay.texts.is_empty());
+
+		// The halo draws a second, larger square behind each handle
+		bounding_box.handle_halo = true;
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.squares.len(), 16);
+		assert_eq!(overlay.squares[0], (positions[0], Some(6. + BOUNDS_HANDLE_HALO_WIDTH * 2.)));
+
+		bounding_box.debug = true;
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.texts, bounding_box.debug_lines());
+	}
+
+	#[test]
+	fn hidden_overlays_draw_nothing() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			debug: true,
+			visible: false,
+			..Default::default()
+		};
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert!(overlay.is_empty());
+
+		bounding_box.visible = true;
+		bounding_box.render_overlays(&mut overlay);
+		assert!(!overlay.is_empty());
+	}
+
+	#[test]
+	fn corner_resize_snaps_to_ratio() {
+		let golden_ratio = (1. + 5_f64.sqrt()) / 2.;
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::splat(100.)]);
+		selected_edges.set_snap_ratios(vec![1., 4. / 3., 16. / 9., golden_ratio]);
+
+		// Dragging close to 16:9 locks to it
+		let (min, size) = selected_edges.new_size(DVec2::new(176., 100.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(min, DVec2::ZERO);
+		assert!(size.abs_diff_eq(DVec2::new(1600. / 9., 100.), 1e-9), "{size}");
+		assert_eq!(selected_edges.snapped_ratio(DVec2::new(176., 100.)), Some(16. / 9.));
+		assert_eq!(ratio_name(16. / 9.), "16:9");
+
+		// Between the ratios the size is left as it is
+		let (_, size) = selected_edges.new_size(DVec2::new(150., 100.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(150., 100.));
+		assert_eq!(selected_edges.snapped_ratio(size), None);
+
+		assert_eq!(selected_edges.snapped_ratio(DVec2::new(162., 100.)), Some(golden_ratio));
+		assert_eq!(ratio_name(golden_ratio), "Golden ratio");
+		assert_eq!(ratio_name(2.5), "2.500:1");
+	}
+
+	#[test]
+	fn rotation_in_progress() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			show_rotation_compass: true,
+			..Default::default()
+		};
+		assert!(!bounding_box.is_rotating());
+
+		bounding_box.begin_rotation(DVec2::new(120., 50.));
+		assert!(bounding_box.is_rotating());
+		bounding_box.applied_rotation = Some(0.5);
+		assert!(bounding_box.rotation_compass().is_some());
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.lines.len(), 5);
+
+		bounding_box.end_rotation();
+		assert!(!bounding_box.is_rotating());
+		assert_eq!(bounding_box.applied_rotation, None);
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert!(overlay.lines.is_empty());
+
+		// A leftover angle without a rotation in progress draws nothing rotation specific
+		bounding_box.applied_rotation = Some(0.5);
+		assert!(bounding_box.rotation_compass().is_none());
+	}
+
+	#[test]
+	fn cancel_restores_original_transforms() {
+		use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+		use graph_craft::document::NodeId;
+
+		let layer = LayerNodeIdentifier::new_unchecked(NodeId(1));
+		let original = OriginalTransforms::Layer([(layer, DAffine2::from_translation(DVec2::new(5., 6.)))].into_iter().collect());
+		let bounds = [DVec2::ZERO, DVec2::splat(100.)];
+		let mut bounding_box = BoundingBoxManager {
+			bounds,
+			original_bound_transform: DAffine2::from_translation(DVec2::new(5., 6.)),
+			transform: DAffine2::from_angle(0.5),
+			selected_edges: Some(SelectedEdges::new(false, true, false, true, bounds)),
+			original_transforms: original.clone(),
+			numeric_override: Some(40.),
+			..Default::default()
+		};
+		bounding_box.begin_rotation(DVec2::new(120., 50.));
+		bounding_box.applied_rotation = Some(0.5);
+
+		assert_eq!(bounding_box.cancel(), original);
+		assert_eq!(bounding_box.transform, DAffine2::from_translation(DVec2::new(5., 6.)));
+		assert_eq!(bounding_box.selected_edges, None);
+		assert!(!bounding_box.is_rotating());
+		assert_eq!(bounding_box.applied_rotation, None);
+		assert_eq!(bounding_box.numeric_override, None);
+		assert_eq!(bounding_box.original_transforms, OriginalTransforms::default());
+	}
+
+	#[test]
+	fn edges_stick_to_guides() {
+		let mut selected_edges = SelectedEdges::new(false, false, false, true, [DVec2::ZERO, DVec2::splat(50.)]);
+		selected_edges.set_guides(vec![100.], vec![20.]);
+		let mut drag = |x: f64| selected_edges.stick_to_guides(DVec2::new(x, 25.), DAffine2::IDENTITY);
+
+		assert_eq!(drag(106.), DVec2::new(106., 25.));
+		// Sticks once close
+		assert_eq!(drag(104.), DVec2::new(100., 25.));
+		// Jittering around within the release threshold stays on the guide
+		for x in [110., 95., 111., 89.] {
+			assert_eq!(drag(x), DVec2::new(100., 25.));
+		}
+		// Pulling far enough away releases it, until close enough to stick again
+		assert_eq!(drag(113.), DVec2::new(113., 25.));
+		assert_eq!(drag(108.), DVec2::new(108., 25.));
+		assert_eq!(drag(103.), DVec2::new(100., 25.));
+
+		// The horizontal guide isn't used since the dragged edge is vertical, and thresholds are in viewport space
+		let mut selected_edges = SelectedEdges::new(false, true, false, false, [DVec2::ZERO, DVec2::splat(50.)]);
+		selected_edges.set_guides(vec![100.], vec![20.]);
+		let transform = DAffine2::from_scale(DVec2::splat(2.));
+		assert_eq!(selected_edges.stick_to_guides(DVec2::new(199., 46.), transform), DVec2::new(199., 46.));
+		assert_eq!(selected_edges.stick_to_guides(DVec2::new(199., 44.), transform), DVec2::new(199., 40.));
+	}
+
+	#[test]
+	fn local_and_screen_round_trip() {
+		let bounding_box = BoundingBoxManager {
+			transform: DAffine2::from_scale_angle_translation(DVec2::new(2., 0.5), 0.7, DVec2::new(-30., 12.)),
+			..Default::default()
+		};
+		for point in [DVec2::ZERO, DVec2::new(100., -50.), DVec2::new(0.25, 1e4)] {
+			assert!(bounding_box.to_screen(bounding_box.to_local(point)).abs_diff_eq(point, 1e-9));
+			assert!(bounding_box.to_local(bounding_box.to_screen(point)).abs_diff_eq(point, 1e-9));
+		}
+		assert_eq!(bounding_box.to_screen(DVec2::ZERO), DVec2::new(-30., 12.));
+	}
+
+	#[test]
+	fn corner_radius_handles() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(200., 100.)],
+			..Default::default()
+		};
+		assert_eq!(bounding_box.radius_handle_positions(), None);
+		assert_eq!(bounding_box.check_radius_handle(DVec2::splat(30.)), None);
+
+		bounding_box.corner_radius = Some(30.);
+		let positions = bounding_box.radius_handle_positions().unwrap();
+		assert_eq!(positions, [DVec2::new(30., 30.), DVec2::new(170., 30.), DVec2::new(170., 70.), DVec2::new(30., 70.)]);
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.circles, positions.to_vec());
+
+		// Small radii keep the handles clear of the corner resize handles, and large ones stop at the center
+		bounding_box.corner_radius = Some(2.);
+		assert_eq!(bounding_box.radius_handle_positions().unwrap()[0], DVec2::splat(BOUNDS_RADIUS_HANDLE_MIN_INSET));
+		bounding_box.corner_radius = Some(500.);
+		assert_eq!(bounding_box.radius_handle_positions().unwrap()[2], DVec2::new(100., 50.));
+
+		// Radius handles are hit separately from the resize handles
+		bounding_box.corner_radius = Some(30.);
+		assert_eq!(bounding_box.check_radius_handle(DVec2::new(172., 68.)), Some(2));
+		assert_eq!(bounding_box.check_radius_handle(DVec2::ZERO), None);
+		assert_eq!(
+			bounding_box.check_selected_edges(DVec2::ZERO),
+			Some(EdgeSelection {
+				top: true,
+				left: true,
+				..Default::default()
+			})
+		);
+
+		// Dragging the bottom right handle inwards grows the radius and outwards shrinks it
+		assert_eq!(bounding_box.radius_delta(2, DVec2::new(170., 70.), DVec2::new(160., 60.)), Some(10.));
+		assert_eq!(bounding_box.radius_delta(2, DVec2::new(170., 70.), DVec2::new(175., 75.)), Some(-5.));
+		assert_eq!(bounding_box.radius_delta(0, DVec2::new(30., 30.), DVec2::new(40., 30.)), Some(5.));
+		assert_eq!(bounding_box.radius_delta(4, DVec2::new(30., 30.), DVec2::new(40., 30.)), None);
+	}
+
+	#[test]
+	fn group_delta_applies_to_children() {
+		let pivot = DVec2::new(50., 50.);
+		let about_pivot = |transform: DAffine2| DAffine2::from_translation(pivot) * transform * DAffine2::from_translation(-pivot);
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			transform: about_pivot(DAffine2::from_angle(FRAC_PI_2)),
+			..Default::default()
+		};
+
+		// Two children on either side of the group are rotated around its pivot
+		let left = bounding_box.apply_delta_to(DAffine2::from_translation(DVec2::new(10., 0.)));
+		let right = bounding_box.apply_delta_to(DAffine2::from_translation(DVec2::new(90., 0.)));
+		assert!(left.translation.abs_diff_eq(DVec2::new(100., 10.), 1e-10), "{}", left.translation);
+		assert!(right.translation.abs_diff_eq(DVec2::new(100., 90.), 1e-10), "{}", right.translation);
+		assert!(left.matrix2.abs_diff_eq(DAffine2::from_angle(FRAC_PI_2).matrix2, 1e-10));
+		assert!(right.matrix2.abs_diff_eq(left.matrix2, 1e-10));
+
+		// A non-uniform group scale stretches an unrotated child without skewing it
+		bounding_box.transform = about_pivot(DAffine2::from_scale(DVec2::new(2., 1.)));
+		let child = bounding_box.apply_delta_to(DAffine2::from_scale_angle_translation(DVec2::splat(3.), 0., DVec2::new(10., 20.)));
+		assert_eq!(child.matrix2.x_axis.dot(child.matrix2.y_axis), 0.);
+		assert_eq!(child.matrix2, glam::DMat2::from_diagonal(DVec2::new(6., 3.)));
+		assert_eq!(child.translation, DVec2::new(-30., 20.));
+	}
+
+	#[test]
+	fn rotation_begins_without_jump() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(100.)],
+			center_of_transformation: DVec2::new(50., 50.),
+			..Default::default()
+		};
+		assert_eq!(bounding_box.rotation_delta(DVec2::new(0., 50.), false), 0.);
+
+		// Grabbing the bottom right corner
+		let corner = DVec2::new(100., 100.);
+		bounding_box.begin_rotation(corner);
+		assert!(bounding_box.rotation_delta(corner, false).abs() < 1e-12);
+		assert!(bounding_box.rotation_delta(corner + DVec2::new(1e-6, -1e-6), false).abs() < 1e-6);
+
+		// The corner follows the cursor from there
+		let quarter_turn = bounding_box.rotation_delta(DVec2::new(0., 100.), false);
+		assert!((quarter_turn - FRAC_PI_2).abs() < 1e-10);
+		assert!((bounding_box.rotation_delta(DVec2::new(100., 0.), false) + FRAC_PI_2).abs() < 1e-10);
+
+		bounding_box.end_rotation();
+		assert_eq!(bounding_box.rotation_start_angle, None);
+	}
+
+	#[test]
+	fn handle_shapes_change_drawing_but_not_hit_testing() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let cursor = DVec2::new(1., 1.);
+		let edges = bounding_box.check_selected_edges(cursor);
+
+		bounding_box.handle_shapes[0] = HandleShape::Diamond;
+		bounding_box.handle_shapes[7] = HandleShape::Circle;
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		let positions = bounding_box.displayed_handle_positions();
+		assert_eq!(overlay.squares.len(), 6);
+		assert_eq!(overlay.diamonds, vec![positions[0]]);
+		assert_eq!(overlay.circles, vec![positions[7]]);
+		assert_eq!(bounding_box.check_selected_edges(cursor), edges);
+	}
+
+	#[test]
+	fn resize_keeping_fixed_point_stationary() {
+		let selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::new(100., 50.)]);
+
+		// A fixed point inside the bounds
+		let fixed = DVec2::new(25., 25.);
+		let transform = selected_edges.resize_keeping_fixed(fixed, DVec2::new(200., 100.));
+		assert_eq!(transform.transform_point2(fixed), fixed);
+		assert_eq!(transform.transform_point2(DVec2::ZERO), DVec2::new(-25., -25.));
+		assert_eq!(transform.transform_point2(DVec2::new(100., 50.)), DVec2::new(175., 75.));
+
+		// A fixed point outside the bounds
+		let fixed = DVec2::new(-100., 150.);
+		let transform = selected_edges.resize_keeping_fixed(fixed, DVec2::new(50., 25.));
+		assert_eq!(transform.transform_point2(fixed), fixed);
+		assert_eq!(transform.transform_point2(DVec2::ZERO), DVec2::new(-50., 75.));
+		assert_eq!(transform.transform_vector2(DVec2::new(100., 50.)), DVec2::new(50., 25.));
+
+		// An axis with no size is left unscaled
+		let selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::new(0., 50.)]);
+		let transform = selected_edges.resize_keeping_fixed(DVec2::new(10., 0.), DVec2::new(80., 100.));
+		assert_eq!(transform.transform_point2(DVec2::new(0., 50.)), DVec2::new(0., 100.));
+	}
+
+	#[test]
+	fn axis_guide_follows_locked_axis() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let start = DVec2::new(50., 50.);
+
+		// Disabled by default
+		bounding_box.update_axis_guide(true, start, DVec2::new(60., 90.));
+		assert_eq!(bounding_box.axis_guide, None);
+
+		bounding_box.show_axis_guide = true;
+		bounding_box.update_axis_guide(true, start, DVec2::new(60., 90.));
+		let [guide_start, guide_end] = bounding_box.axis_guide_line().unwrap();
+		assert!((guide_start - start).perp_dot(DVec2::Y).abs() < 1e-6 && (guide_end - start).perp_dot(DVec2::Y).abs() < 1e-6);
+
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.lines, vec![[guide_start, guide_end]]);
+
+		// Only shown while axis aligning
+		bounding_box.update_axis_guide(false, start, DVec2::new(60., 90.));
+		assert_eq!(bounding_box.axis_guide, None);
+	}
+
+	#[test]
+	fn sub_threshold_jitter_is_ignored() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			center_of_transformation: DVec2::new(50., 25.),
+			min_move_threshold: 2.,
+			..Default::default()
+		};
+		let start = DVec2::new(100., 25.);
+		bounding_box.begin_rotation(start);
+		let selected_edges = SelectedEdges::new(false, false, false, true, bounding_box.bounds);
+
+		let steadied = bounding_box.steady_cursor(DVec2::new(101., 25.5));
+		assert_eq!(steadied, start);
+		assert_eq!(bounding_box.rotation_delta(steadied, false), 0.);
+		let resize = |cursor| selected_edges.new_size(cursor, DAffine2::IDENTITY, None, false, None);
+		assert_eq!(resize(steadied), resize(start));
+
+		// Moving past the threshold is accepted and becomes the new reference
+		let moved = DVec2::new(100., 28.);
+		assert_eq!(bounding_box.steady_cursor(moved), moved);
+		assert_eq!(bounding_box.steady_cursor(DVec2::new(101., 28.)), moved);
+
+		// With the default threshold every movement is accepted
+		bounding_box.min_move_threshold = 0.;
+		assert_eq!(bounding_box.steady_cursor(DVec2::new(100.5, 28.)), DVec2::new(100.5, 28.));
+	}
+
+	#[test]
+	fn area_of_transformed_cage() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::new(2., 3.), 0.7, DVec2::new(30., -20.)),
+			..Default::default()
+		};
+		assert!((bounding_box.area() - 100. * 50. * 2. * 3.).abs() < 1e-6);
+
+		// A reflected cage reports the same positive area
+		bounding_box.transform = DAffine2::from_scale(DVec2::new(-2., 3.));
+		assert!((bounding_box.area() - 30_000.).abs() < 1e-6);
+	}
+
+	#[test]
+	fn inertia_decays_to_a_stop() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let dt = 1. / 60.;
+
+		// Opt-in only
+		bounding_box.record_velocity(DVec2::splat(1.5), 0.1, dt);
+		assert_eq!(bounding_box.step_inertia(dt), None);
+
+		bounding_box.inertia = true;
+		bounding_box.record_velocity(DVec2::splat(1.5), 0.1, dt);
+		let mut previous_scale = f64::INFINITY;
+		let mut steps = 0;
+		while let Some(delta) = bounding_box.step_inertia(dt) {
+			let scale = delta.matrix2.determinant();
+			assert!(scale > 1. && scale < previous_scale);
+			previous_scale = scale;
+			steps += 1;
+			assert!(steps < 1000, "Inertia should settle in a finite number of steps");
+		}
+		assert!(steps > 1);
+		assert_eq!(bounding_box.inertia_velocity, None);
+		assert!(bounding_box.transform.matrix2.determinant() > 1.);
+	}
+
+	#[test]
+	fn readouts_stay_upright() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_angle(PI),
+			..Default::default()
+		};
+		// The local top left corner is at the bottom right on screen when the cage is upside down
+		let anchor = bounding_box.corners()[0];
+		let (transform, pivot) = bounding_box.readout_placement(anchor, 8.);
+		assert_eq!(transform.matrix2, glam::DMat2::IDENTITY);
+		let [min, max] = bounding_box.aabb();
+		assert!((transform.translation - (min + max) / 2.).length() > (anchor - (min + max) / 2.).length());
+		assert!(matches!(pivot, [Pivot::Start, Pivot::Start]));
+	}
+
+	#[test]
+	fn nearest_edge_outside_bounds() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_scale(DVec2::splat(2.)),
+			..Default::default()
+		};
+		let edges = |top, bottom, left, right| EdgeSelection { top, bottom, left, right };
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(100., -500.)), None);
+		assert_eq!(bounding_box.nearest_edge(DVec2::new(100., -500.)), edges(true, false, false, false));
+		assert_eq!(bounding_box.nearest_edge(DVec2::new(100., 600.)), edges(false, true, false, false));
+		assert_eq!(bounding_box.nearest_edge(DVec2::new(-500., 50.)), edges(false, false, true, false));
+		assert_eq!(bounding_box.nearest_edge(DVec2::new(700., 50.)), edges(false, false, false, true));
+		assert_eq!(bounding_box.nearest_edge(DVec2::new(700., 600.)), edges(false, true, false, true));
+
+		// Inside the bounds, the closest single edge
+		assert_eq!(bounding_box.nearest_edge(DVec2::new(190., 50.)), edges(false, false, false, true));
+		assert_eq!(bounding_box.nearest_edge(DVec2::new(100., 10.)), edges(true, false, false, false));
+	}
+
+	#[test]
+	fn integer_ratio_snaps_exactly() {
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::splat(100.)]);
+		let ratios = vec![(1, 1), (2, 1), (1, 2), (3, 2)];
+
+		// Without the mode active, the size follows the mouse
+		let (_, size) = selected_edges.new_size(DVec2::new(205., 98.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(205., 98.));
+
+		selected_edges.set_integer_ratios(Some(ratios));
+		let (min, size) = selected_edges.new_size(DVec2::new(205., 98.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(min, DVec2::ZERO);
+		assert_eq!(size, DVec2::new(205., 102.5));
+		assert_eq!(selected_edges.locked_ratio(size), Some(2.));
+
+		// Dragging a single edge keeps the dragged axis
+		let mut selected_edges = SelectedEdges::new(false, false, false, true, [DVec2::ZERO, DVec2::splat(100.)]);
+		selected_edges.set_integer_ratios(Some(vec![(1, 1), (2, 1)]));
+		let (_, size) = selected_edges.new_size(DVec2::new(180., 50.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(180., 90.));
+	}
+
+	#[test]
+	fn mouse_projection_matches_resize() {
+		let selected_edges = SelectedEdges::new(true, false, false, true, [DVec2::ZERO, DVec2::new(100., 50.)]);
+		let transform = DAffine2::from_scale_angle_translation(DVec2::new(2., 0.5), 0.4, DVec2::new(10., 20.));
+		let mouse = DVec2::new(230., 80.);
+
+		let local = selected_edges.project_mouse_local(mouse, transform);
+		assert!((transform.transform_point2(local) - mouse).length() < 1e-9);
+		let (min, size) = selected_edges.new_size(mouse, transform, None, false, None);
+		assert_eq!(min.y, local.y);
+		assert_eq!(min.x + size.x, local.x);
+	}
+
+	#[test]
+	fn rollback_to_snap_checkpoints() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let first = DAffine2::from_angle(15_f64.to_radians());
+		let second = DAffine2::from_angle(30_f64.to_radians());
+
+		bounding_box.transform = first;
+		bounding_box.update_engaged_snap(Some(SnapEvent::Angle(15_f64.to_radians())));
+		// Staying on the same snap doesn't record another checkpoint
+		bounding_box.update_engaged_snap(Some(SnapEvent::Angle(15_f64.to_radians())));
+		bounding_box.transform = second;
+		bounding_box.update_engaged_snap(Some(SnapEvent::Angle(30_f64.to_radians())));
+		bounding_box.transform = DAffine2::from_angle(0.7);
+		assert_eq!(bounding_box.checkpoints, vec![first, second]);
+
+		assert_eq!(bounding_box.rollback_to_checkpoint(), Some(second));
+		assert_eq!(bounding_box.transform, second);
+		assert_eq!(bounding_box.rollback_to_checkpoint(), Some(first));
+		assert_eq!(bounding_box.rollback_to_checkpoint(), None);
+		assert_eq!(bounding_box.transform, first);
+
+		// Ending the drag clears any checkpoints which weren't taken
+		bounding_box.record_checkpoint();
+		bounding_box.end_rotation();
+		assert!(bounding_box.checkpoints.is_empty());
+	}
+
+	#[test]
+	fn pivot_clamps_into_rotated_bounds() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_angle(FRAC_PI_2),
+			..Default::default()
+		};
+		// Pivots outside the bounds are allowed by default
+		let outside = DVec2::new(-80., 120.);
+		assert_eq!(bounding_box.set_pivot(outside), outside);
+
+		// The cage covers x in -50..0 and y in 0..100 on screen, so the nearest interior point is on its bottom left corner
+		bounding_box.clamp_pivot_to_bounds = true;
+		let pivot = bounding_box.set_pivot(outside);
+		assert!((pivot - DVec2::new(-50., 100.)).length() < 1e-9);
+		assert_eq!(bounding_box.center_of_transformation, pivot);
+
+		// Points inside aren't moved
+		let inside = DVec2::new(-20., 30.);
+		assert!((bounding_box.set_pivot(inside) - inside).length() < 1e-9);
+	}
+
+	#[test]
+	fn resize_highlights_anchor_and_grabbed_handles() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut bounding_box = BoundingBoxManager {
+			bounds,
+			selected_edges: Some(SelectedEdges::new(false, true, false, true, bounds)),
+			..Default::default()
+		};
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+
+		// Dragging the bottom right corner keeps the top left fixed
+		let anchor = Some(bounding_box.anchor_handle_color.clone());
+		let grabbed = Some(bounding_box.grabbed_handle_color.clone());
+		let expected = [anchor, None, None, None, None, None, None, grabbed];
+		assert_eq!(overlay.square_fills, expected.to_vec());
+
+		// An anchor takes the place of the opposite handle, and resizing about the center highlights no fixed handle
+		let selected_edges = bounding_box.selected_edges.as_mut().unwrap();
+		selected_edges.set_anchor(PivotPosition::CenterRight);
+		assert_eq!(selected_edges.opposite_handle(), Some(6));
+		selected_edges.set_anchor(PivotPosition::Center);
+		assert_eq!(selected_edges.opposite_handle(), None);
+
+		bounding_box.selected_edges = None;
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert!(overlay.square_fills.iter().all(Option::is_none));
+	}
+
+	#[test]
+	fn gesture_rotation_accumulates() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let pivot = DVec2::new(50., 25.);
+		for delta in [0.1, 0.2, -0.05] {
+			bounding_box.apply_gesture_rotation(delta, pivot);
+		}
+		assert!((bounding_box.applied_rotation.unwrap() - 0.25).abs() < 1e-9);
+		let rotation = bounding_box.transform.matrix2 * DVec2::X;
+		assert!((rotation.to_angle() - 0.25).abs() < 1e-9);
+		assert!((bounding_box.transform.transform_point2(pivot) - pivot).length() < 1e-9);
+
+		// Snapping and clamping apply to the accumulated total
+		bounding_box.end_rotation();
+		bounding_box.snap_gesture_rotation = true;
+		bounding_box.apply_gesture_rotation(10_f64.to_radians(), pivot);
+		bounding_box.apply_gesture_rotation(10_f64.to_radians(), pivot);
+		assert!((bounding_box.applied_rotation.unwrap() - 15_f64.to_radians()).abs() < 1e-9);
+		bounding_box.max_angle = Some(0.1);
+		bounding_box.apply_gesture_rotation(10_f64.to_radians(), pivot);
+		assert_eq!(bounding_box.applied_rotation, Some(0.1));
+	}
+
+	#[test]
+	fn overlapping_cages() {
+		let rotated = |min: DVec2, max: DVec2| BoundingBoxManager {
+			bounds: [min, max],
+			transform: DAffine2::from_angle(0.6),
+			..Default::default()
+		};
+		let cage = rotated(DVec2::ZERO, DVec2::splat(10.));
+		assert!(cage.overlaps(&rotated(DVec2::splat(5.), DVec2::splat(15.))));
+		assert!(!cage.overlaps(&rotated(DVec2::new(12., 0.), DVec2::new(20., 10.))));
+		// Sharing an edge is only touching
+		assert!(!cage.overlaps(&rotated(DVec2::new(10., 0.), DVec2::new(20., 10.))));
+		assert!(!cage.overlaps(&rotated(DVec2::splat(10.), DVec2::splat(20.))));
+
+		// A cage rotated against the other, which separates along one of its own edges
+		let diamond = BoundingBoxManager {
+			bounds: [DVec2::splat(-5.), DVec2::splat(5.)],
+			transform: DAffine2::from_angle_translation(PI / 4., DVec2::new(-6., 0.)),
+			..Default::default()
+		};
+		let square = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(10.)],
+			..Default::default()
+		};
+		assert!(diamond.overlaps(&square));
+		let diamond = BoundingBoxManager {
+			transform: DAffine2::from_angle_translation(PI / 4., DVec2::new(-7.1, -7.1)),
+			..diamond
+		};
+		assert!(!diamond.overlaps(&square));
+	}
+
+	#[test]
+	fn rotate_band_scales_with_large_cages() {
+		let cage = |size: f64| BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::splat(size)],
+			rotate_threshold_fraction: Some(0.1),
+			..Default::default()
+		};
+
+		// Small selections keep the fixed band
+		let small = cage(50.);
+		assert_eq!(small.rotate_threshold(), BOUNDS_ROTATE_THRESHOLD);
+		assert!(small.check_rotate(DVec2::new(25., 50. + BOUNDS_ROTATE_THRESHOLD - 1.)));
+		assert!(!small.check_rotate(DVec2::new(25., 50. + BOUNDS_ROTATE_THRESHOLD + 1.)));
+
+		// Large selections get a band proportional to their size
+		let large = cage(1000.);
+		assert_eq!(large.rotate_threshold(), 100.);
+		assert!(large.check_rotate(DVec2::new(500., 1099.)));
+		assert!(!large.check_rotate(DVec2::new(500., 1101.)));
+		assert!(!BoundingBoxManager {
+			rotate_threshold_fraction: None,
+			..large
+		}
+		.check_rotate(DVec2::new(500., 1099.)));
+	}
+
+	#[test]
+	fn corner_moves_along_rotation_arc() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			center_of_transformation: DVec2::new(50., 25.),
+			..Default::default()
+		};
+		let pivot = bounding_box.center_of_transformation;
+		let corner = bounding_box.corners()[2];
+		for angle in [0.3, FRAC_PI_2, 2.5] {
+			let moved = bounding_box.corner_after(2, DAffine2::from_angle(angle)).unwrap();
+			assert!((moved.distance(pivot) - corner.distance(pivot)).abs() < 1e-9);
+			assert!(((corner - pivot).angle_to(moved - pivot) - angle).abs() < 1e-9);
+		}
+		assert_eq!(bounding_box.corner_after(4, DAffine2::IDENTITY), None);
+	}
+
+	#[test]
+	fn trial_resize_leaves_cage_unchanged() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::splat(2.), 0.3, DVec2::new(10., 10.)),
+			..Default::default()
+		};
+		let before = bounding_box.clone();
+		let edges = SelectedEdges::new(false, true, false, true, bounding_box.bounds);
+		let mouse = bounding_box.to_screen(DVec2::new(150., 100.));
+
+		let (bounds, transform) = bounding_box.trial_resize(&edges, mouse, ResizeModifiers::default());
+		assert_eq!(bounding_box, before);
+		assert!((bounds[0] - DVec2::ZERO).length() < 1e-9 && (bounds[1] - DVec2::new(150., 100.)).length() < 1e-9);
+		for (original, resized) in bounding_box.bounds.into_iter().zip(bounds) {
+			assert!((transform.transform_point2(original) - bounding_box.to_screen(resized)).length() < 1e-9);
+		}
+
+		let (bounds, _) = bounding_box.trial_resize(&edges, mouse, ResizeModifiers { center: true, constrain: false });
+		assert!((bounds[0] - DVec2::new(-50., -50.)).length() < 1e-9);
+	}
+
+	#[test]
+	fn explicit_aspect_ratio_lock() {
+		let mut selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::new(100., 50.)]);
+		selected_edges.set_aspect_ratio(1.);
+		let (min, size) = selected_edges.new_size(DVec2::new(120., 80.), DAffine2::IDENTITY, None, true, None);
+		assert_eq!(min, DVec2::ZERO);
+		assert_eq!(size, DVec2::splat(120.));
+
+		// Invalid ratios are ignored
+		selected_edges.set_aspect_ratio(0.);
+		selected_edges.set_aspect_ratio(-2.);
+		selected_edges.set_aspect_ratio(f64::NAN);
+		let (_, size) = selected_edges.new_size(DVec2::new(120., 80.), DAffine2::IDENTITY, None, true, None);
+		assert_eq!(size, DVec2::splat(120.));
+	}
+
+	#[test]
+	fn clipped_overlays_skip_offscreen_handles() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(-50., 20.), DVec2::new(50., 80.)],
+			viewport_clip: Some([DVec2::ZERO, DVec2::splat(100.)]),
+			..Default::default()
+		};
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+
+		// The handles on the left edge are off screen
+		let positions = bounding_box.displayed_handle_positions();
+		let drawn: Vec<_> = overlay.squares.iter().map(|&(position, _)| position).collect();
+		assert_eq!(drawn, positions[3..].to_vec());
+
+		// The outline is clipped to the visible part rather than drawn as a whole quad
+		assert!(overlay.quads.is_empty());
+		assert_eq!(
+			overlay.lines,
+			vec![
+				[DVec2::new(0., 20.), DVec2::new(50., 20.)],
+				[DVec2::new(50., 20.), DVec2::new(50., 80.)],
+				[DVec2::new(50., 80.), DVec2::new(0., 80.)],
+			]
+		);
+
+		// A cage entirely within the visible area is drawn as normal
+		bounding_box.bounds = [DVec2::splat(20.), DVec2::splat(80.)];
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.quads.len(), 1);
+		assert_eq!(overlay.squares.len(), 8);
+	}
+
+	#[test]
+	fn resize_application_of_path_points() {
+		let mut selected_edges = SelectedEdges::new(false, false, false, true, [DVec2::ZERO, DVec2::new(100., 50.)]);
+		let (position, size) = selected_edges.new_size(DVec2::new(200., 25.), DAffine2::IDENTITY, None, false, None);
+		let (transform, application) = selected_edges.resize_transform(position, size);
+		assert_eq!(application, ResizeApplication::Full);
+		assert_eq!(transform.transform_point2(DVec2::new(100., 50.)), DVec2::new(200., 50.));
+
+		let (anchor, handle) = (DVec2::new(50., 10.), DVec2::new(60., 20.));
+		assert_eq!(application.handle(transform, anchor, handle), DVec2::new(120., 20.));
+
+		selected_edges.set_resize_application(ResizeApplication::AnchorsOnly);
+		let (transform, application) = selected_edges.resize_transform(position, size);
+		assert_eq!(application.anchor(transform, anchor), DVec2::new(100., 10.));
+		assert_eq!(application.handle(transform, anchor, handle), DVec2::new(110., 20.));
+
+		let application = ResizeApplication::HandlesOnly;
+		assert_eq!(application.anchor(transform, anchor), anchor);
+		assert_eq!(application.handle(transform, anchor, handle), DVec2::new(70., 20.));
+	}
+
+	#[test]
+	fn scale_factor_of_resize() {
+		let selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::new(100., 50.)]);
+		assert_eq!(selected_edges.scale_factor(DVec2::new(120., 25.)), DVec2::new(1.2, 0.5));
+		assert_eq!(selected_edges.scale_factor(DVec2::new(-100., 50.)), DVec2::new(-1., 1.));
+
+		let selected_edges = SelectedEdges::new(false, true, false, true, [DVec2::ZERO, DVec2::new(100., 0.)]);
+		assert_eq!(selected_edges.scale_factor(DVec2::new(50., 30.)), DVec2::new(0.5, 1.));
+	}
+
+	#[test]
+	fn rounded_outline_is_cosmetic() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			outline_corner_radius: 8.,
+			..Default::default()
+		};
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert!(overlay.quads.is_empty());
+		assert_eq!(overlay.rounded_quads, vec![(bounding_box.corners(), 8.)]);
+
+		// Hit testing still uses the sharp corners
+		let corner_hit = bounding_box.check_selected_edges(DVec2::new(1., 1.));
+		bounding_box.outline_corner_radius = 0.;
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(1., 1.)), corner_hit);
+		let mut overlay = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlay);
+		assert_eq!(overlay.quads.len(), 1);
+		assert!(overlay.rounded_quads.is_empty());
+	}
+
+	#[test]
+	fn handle_layout_roles_match_positions() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_angle_translation(0.5, DVec2::new(20., 30.)),
+			..Default::default()
+		};
+		let corners = bounding_box.corners();
+		let midpoints: Vec<_> = (0..4).map(|index| corners[index].lerp(corners[(index + 1) % 4], 0.5)).collect();
+		let near = |a: DVec2, b: DVec2| a.distance(b) < 1e-9;
+
+		for (position, role) in bounding_box.handle_layout() {
+			match role {
+				HandleRole::ResizeCorner => assert!(corners.iter().any(|&corner| near(corner, position))),
+				HandleRole::ResizeEdge => assert!(midpoints.iter().any(|&midpoint| near(midpoint, position))),
+			}
+			// Grabbing the handle performs its role
+			let edges = bounding_box.check_selected_edges(position).unwrap();
+			assert_eq!(edges.is_corner(), role == HandleRole::ResizeCorner);
+		}
+	}
+
+	#[test]
+	fn pivot_snaps_to_guide_intersections() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let (vertical, horizontal) = ([30., 70.], [10.]);
+		assert_eq!(bounding_box.snap_pivot(DVec2::new(32., 12.), &vertical, &horizontal), DVec2::new(30., 10.));
+		assert_eq!(bounding_box.snap_pivot(DVec2::new(51., 24.), &vertical, &horizontal), DVec2::new(50., 25.));
+		assert_eq!(bounding_box.snap_pivot(DVec2::new(2., 1.), &vertical, &horizontal), DVec2::ZERO);
+
+		// The nearest target wins when a guide intersection is close to a handle
+		let horizontal = [3.];
+		assert_eq!(bounding_box.snap_pivot(DVec2::new(70., 2.), &vertical, &horizontal), DVec2::new(70., 3.));
+		assert_eq!(bounding_box.snap_pivot(DVec2::new(40., 40.), &vertical, &horizontal), DVec2::new(40., 40.));
+	}
+
+	#[test]
+	fn fit_cage_into_target() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(10., 10.), DVec2::new(110., 60.)],
+			..Default::default()
+		};
+		let target = [DVec2::ZERO, DVec2::new(400., 400.)];
+
+		let stretch = bounding_box.fit_into(target, false);
+		assert_eq!(stretch.transform_point2(DVec2::new(10., 10.)), DVec2::ZERO);
+		assert_eq!(stretch.transform_point2(DVec2::new(110., 60.)), DVec2::new(400., 400.));
+
+		// Letterboxed, so the wider side fills the target and the height is centered
+		let letterbox = bounding_box.fit_into(target, true);
+		assert_eq!(letterbox.transform_point2(DVec2::new(10., 10.)), DVec2::new(0., 100.));
+		assert_eq!(letterbox.transform_point2(DVec2::new(110., 60.)), DVec2::new(400., 300.));
+
+		// A cage with no height only scales its width
+		let line = BoundingBoxManager {
+			bounds: [DVec2::new(0., 20.), DVec2::new(100., 20.)],
+			..Default::default()
+		};
+		for preserve_aspect in [false, true] {
+			let fit = line.fit_into(target, preserve_aspect);
+			assert!(fit.matrix2.to_cols_array().iter().all(|value| value.is_finite()));
+			assert_eq!(fit.transform_point2(DVec2::new(100., 20.)), DVec2::new(400., 200.));
+		}
+	}
+
+	#[test]
+	fn handle_cursor_overrides() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let mut input = InputPreprocessorMessageHandler::default();
+		input.mouse.position = DVec2::new(100., 50.);
+		assert_eq!(bounding_box.get_cursor(&input, false), MouseCursorIcon::NWSEResize);
+
+		// The bottom right handle
+		bounding_box.set_handle_cursor(7, MouseCursorIcon::Crosshair);
+		assert_eq!(bounding_box.get_cursor(&input, false), MouseCursorIcon::Crosshair);
+
+		// Other handles keep their resize cursors
+		input.mouse.position = DVec2::new(100., 25.);
+		assert_eq!(bounding_box.get_cursor(&input, false), MouseCursorIcon::EWResize);
+
+		bounding_box.clear_handle_cursors();
+		input.mouse.position = DVec2::new(100., 50.);
+		assert_eq!(bounding_box.get_cursor(&input, false), MouseCursorIcon::NWSEResize);
+	}
+
+	#[test]
+	fn click_or_drag_threshold() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		assert!(!bounding_box.is_drag(DVec2::new(500., 500.), 3.), "Nothing was pressed");
+
+		bounding_box.press_position = Some(DVec2::new(100., 50.));
+		assert!(!bounding_box.is_drag(DVec2::new(102.9, 50.), 3.));
+		assert!(!bounding_box.is_drag(DVec2::new(102., 52.), 3.));
+		assert!(bounding_box.is_drag(DVec2::new(103.1, 50.), 3.));
+		assert!(bounding_box.is_drag(DVec2::new(98., 47.), 3.));
+
+		bounding_box.end_rotation();
+		assert!(!bounding_box.is_drag(DVec2::new(103.1, 50.), 3.));
+	}
+
+	#[test]
+	fn symmetric_edge_drag() {
+		let bounds = [DVec2::new(0., 0.), DVec2::new(100., 50.)];
+		let mut edges = SelectedEdges::new(false, false, false, true, bounds);
+		edges.set_symmetric(true);
+
+		// Dragging the right edge 20 further out moves the left edge 20 out the other way
+		let (position, size) = edges.new_size(DVec2::new(120., 30.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(position, DVec2::new(-20., 0.));
+		assert_eq!(size, DVec2::new(140., 50.));
+
+		// Corner drags aren't affected
+		let mut corner = SelectedEdges::new(false, true, false, true, bounds);
+		corner.set_symmetric(true);
+		let (position, size) = corner.new_size(DVec2::new(120., 60.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(position, DVec2::ZERO);
+		assert_eq!(size, DVec2::new(120., 60.));
+	}
+
+	#[test]
+	fn label_anchors_outside_rotated_cage() {
+		// Rotated a quarter turn, so the local top edge runs down the right side of the viewport
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_angle(std::f64::consts::FRAC_PI_2),
+			..Default::default()
+		};
+		let anchors = bounding_box.label_anchors(10.);
+
+		let expected_edges = [DVec2::new(10., 50.), DVec2::new(-25., 110.), DVec2::new(-60., 50.), DVec2::new(-25., -10.)];
+		for (anchor, expected) in anchors.edges.into_iter().zip(expected_edges) {
+			assert!(anchor.abs_diff_eq(expected, 1e-10), "Expected {expected} but got {anchor}");
+		}
+
+		let diagonal = 10. / std::f64::consts::SQRT_2;
+		let expected_corners = [
+			DVec2::new(diagonal, -diagonal),
+			DVec2::new(diagonal, 100. + diagonal),
+			DVec2::new(-50. - diagonal, 100. + diagonal),
+			DVec2::new(-50. - diagonal, -diagonal),
+		];
+		for (anchor, expected) in anchors.corners.into_iter().zip(expected_corners) {
+			assert!(anchor.abs_diff_eq(expected, 1e-10), "Expected {expected} but got {anchor}");
+		}
+	}
+
+	#[test]
+	fn reference_gap_edge_drag() {
+		let bounds = [DVec2::new(0., 0.), DVec2::new(100., 50.)];
+		let mut edges = SelectedEdges::new(false, false, false, true, bounds);
+		edges.set_reference_gap(Some((-30., 20.)));
+
+		// The left edge keeps 20 to the right of the line at -30 however far the right edge is dragged
+		for mouse_x in [150., 60.] {
+			let (position, size) = edges.new_size(DVec2::new(mouse_x, 30.), DAffine2::IDENTITY, None, false, None);
+			assert_eq!(position, DVec2::new(-10., 0.));
+			assert_eq!(size, DVec2::new(mouse_x + 10., 50.));
+		}
+
+		// Dragging the top keeps the bottom edge 5 above a line below the bounds
+		let mut edges = SelectedEdges::new(true, false, false, false, bounds);
+		edges.set_reference_gap(Some((80., 5.)));
+		let (position, size) = edges.new_size(DVec2::new(50., -20.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(position, DVec2::new(0., -20.));
+		assert_eq!(size, DVec2::new(100., 95.));
+	}
+
+	#[test]
+	fn match_rotation_of_other_cage() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let bounding_box = BoundingBoxManager {
+			bounds,
+			transform: DAffine2::from_angle(10_f64.to_radians()),
+			..Default::default()
+		};
+		let other = BoundingBoxManager {
+			bounds,
+			transform: DAffine2::from_translation(DVec2::new(300., 0.)) * DAffine2::from_angle(40_f64.to_radians()),
+			..Default::default()
+		};
+
+		let pivot = DVec2::new(20., 30.);
+		let delta = bounding_box.match_rotation(&other, pivot);
+		let expected = DAffine2::from_translation(pivot) * DAffine2::from_angle(30_f64.to_radians()) * DAffine2::from_translation(-pivot);
+		assert!(delta.abs_diff_eq(expected, 1e-10));
+		assert!(delta.transform_point2(pivot).abs_diff_eq(pivot, 1e-10));
+
+		// Facing the opposite way, the closest orientation of the reflected outline is a small turn
+		let reflected = BoundingBoxManager {
+			bounds,
+			transform: DAffine2::from_angle(200_f64.to_radians()) * DAffine2::from_scale(DVec2::new(1., -1.)),
+			..Default::default()
+		};
+		let (_, angle, _) = bounding_box.match_rotation(&reflected, DVec2::ZERO).to_scale_angle_translation();
+		assert!((angle - 10_f64.to_radians()).abs() < 1e-10, "Expected a 10° turn but got {}°", angle.to_degrees());
+	}
+
+	#[test]
+	fn tiny_selection_hides_edge_handles() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(50., 50.), DVec2::new(53., 53.)],
+			min_handle_spacing: 6.,
+			..Default::default()
+		};
+		assert_eq!(bounding_box.hidden_edge_handles(), [true, true]);
+
+		let mut overlays = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlays);
+		let corners = [DVec2::new(50., 50.), DVec2::new(50., 53.), DVec2::new(53., 50.), DVec2::new(53., 53.)];
+		assert!(!overlays.squares.is_empty());
+		assert!(overlays.squares.iter().all(|&(position, _)| corners.contains(&position)), "Only the corner handles should be drawn");
+
+		// Hovering the middle of an edge grabs a corner, since no edge handle is shown there
+		let edges = bounding_box.check_selected_edges(DVec2::new(54., 51.)).unwrap();
+		assert!(edges.is_corner());
+
+		// Without the spacing, the edge is prioritised on the tiny selection
+		bounding_box.min_handle_spacing = 0.;
+		assert_eq!(bounding_box.hidden_edge_handles(), [false, false]);
+		let edges = bounding_box.check_selected_edges(DVec2::new(54., 51.)).unwrap();
+		assert!(edges.is_edge());
+	}
+
+	#[test]
+	fn trs_round_trip() {
+		let transforms = [
+			DAffine2::IDENTITY,
+			DAffine2::from_scale_angle_translation(DVec2::new(2., 0.5), 0.7, DVec2::new(30., -12.)),
+			// Reflected
+			DAffine2::from_scale_angle_translation(DVec2::new(-1.5, 3.), -2.2, DVec2::new(-4., 80.)),
+		];
+		for transform in transforms {
+			let mut bounding_box = BoundingBoxManager {
+				bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+				transform,
+				..Default::default()
+			};
+			let (translation, rotation, scale) = bounding_box.trs();
+			bounding_box.set_trs(translation, rotation, scale, DVec2::new(45., 45.));
+			assert!(bounding_box.transform.abs_diff_eq(transform, 1e-10), "Expected {transform} but got {}", bounding_box.transform);
+		}
+
+		// Rotating from the panel about a pivot keeps the pivot in place
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_translation(DVec2::new(10., 10.)),
+			..Default::default()
+		};
+		let pivot = DVec2::new(60., 35.);
+		let (translation, _, scale) = bounding_box.trs();
+		bounding_box.set_trs(translation, std::f64::consts::FRAC_PI_2, scale, pivot);
+		assert!(bounding_box.to_screen(DVec2::new(50., 25.)).abs_diff_eq(pivot, 1e-10));
+		assert!(bounding_box.to_screen(DVec2::ZERO).abs_diff_eq(DVec2::new(85., -15.), 1e-10));
+	}
+
+	#[test]
+	fn constrained_resize_hides_edge_handles() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut bounding_box = BoundingBoxManager {
+			bounds,
+			hide_constrained_handles: true,
+			..Default::default()
+		};
+		let drawn = |bounding_box: &mut BoundingBoxManager| {
+			let mut overlays = RecordingOverlay::default();
+			bounding_box.render_overlays(&mut overlays);
+			overlays.squares.into_iter().map(|(position, _)| position).collect::<Vec<_>>()
+		};
+		let handles = bounding_box.evaluate_transform_handle_positions();
+		assert_eq!(drawn(&mut bounding_box), handles);
+
+		// Dragging the right edge with the aspect ratio constrained only shows the corners along with the dragged and opposite handles
+		bounding_box.selected_edges = Some(SelectedEdges::new(false, false, false, true, bounds));
+		bounding_box.constrain_active = true;
+		assert_eq!(drawn(&mut bounding_box), [0, 1, 2, 5, 6, 7].map(|index| handles[index]));
+
+		// Opt-in only
+		bounding_box.hide_constrained_handles = false;
+		assert_eq!(drawn(&mut bounding_box), handles);
+	}
+
+	#[test]
+	fn resize_corner_to_target() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_translation(DVec2::new(10., 10.)),
+			..Default::default()
+		};
+
+		// Move the bottom right corner while the top left stays put
+		let (bounds, transform) = bounding_box.resize_corner_to(2, DVec2::new(210., 110.));
+		assert_eq!(bounds, [DVec2::ZERO, DVec2::new(200., 100.)]);
+		assert!(transform.transform_point2(DVec2::new(100., 50.)).abs_diff_eq(DVec2::new(210., 110.), 1e-10));
+		assert!(transform.transform_point2(DVec2::ZERO).abs_diff_eq(DVec2::new(10., 10.), 1e-10));
+
+		// Moving it onto the fixed corner, or an invalid corner, leaves the cage as it is
+		assert_eq!(bounding_box.resize_corner_to(2, DVec2::new(10., 10.)), (bounding_box.bounds, bounding_box.transform));
+		assert_eq!(bounding_box.resize_corner_to(4, DVec2::new(210., 110.)), (bounding_box.bounds, bounding_box.transform));
+	}
+
+	#[test]
+	fn cursor_hysteresis_between_resize_and_rotate() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let mut input = InputPreprocessorMessageHandler::default();
+		let mut cursor_at = |bounding_box: &mut BoundingBoxManager, distance: f64| {
+			input.mouse.position = DVec2::new(100. + distance, 25.);
+			bounding_box.update_cursor(&input, true)
+		};
+
+		// The boundary between resizing and rotating is `BOUNDS_SELECT_THRESHOLD` outside the right edge
+		let boundary = BOUNDS_SELECT_THRESHOLD;
+		assert_eq!(cursor_at(&mut bounding_box, boundary - 2.), MouseCursorIcon::EWResize);
+		for distance in [boundary + 1., boundary - 1., boundary + 2.5] {
+			assert_eq!(cursor_at(&mut bounding_box, distance), MouseCursorIcon::EWResize, "Resizing should stick {distance} outside the edge");
+		}
+		assert_eq!(cursor_at(&mut bounding_box, boundary + 3.5), MouseCursorIcon::Rotate);
+		for distance in [boundary - 1., boundary + 1., boundary - 2.5] {
+			assert_eq!(cursor_at(&mut bounding_box, distance), MouseCursorIcon::Rotate, "Rotating should stick {distance} outside the edge");
+		}
+		assert_eq!(cursor_at(&mut bounding_box, boundary - 3.5), MouseCursorIcon::EWResize);
+
+		// Without the state, the cursor flips at the boundary
+		input.mouse.position = DVec2::new(100. + boundary + 1., 25.);
+		assert_eq!(bounding_box.get_cursor(&input, true), MouseCursorIcon::Rotate);
+	}
+
+	#[test]
+	fn cursor_position_relative_to_bounds() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(20., 10.), DVec2::new(120., 60.)],
+			transform: DAffine2::from_scale_angle_translation(DVec2::splat(2.), 0.5, DVec2::new(40., 30.)),
+			..Default::default()
+		};
+		let relative = |local: DVec2| bounding_box.cursor_relative(bounding_box.to_screen(local));
+		assert!(relative(DVec2::new(70., 35.)).abs_diff_eq(DVec2::new(0.5, 0.5), 1e-10));
+		assert!(relative(DVec2::new(45., 60.)).abs_diff_eq(DVec2::new(0.25, 1.), 1e-10));
+		assert!(relative(DVec2::new(170., 0.)).abs_diff_eq(DVec2::new(1.5, -0.2), 1e-10));
+
+		// A horizontal line has no height to be relative to
+		let line = BoundingBoxManager {
+			bounds: [DVec2::new(0., 20.), DVec2::new(100., 20.)],
+			..Default::default()
+		};
+		assert_eq!(line.cursor_relative(DVec2::new(25., 40.)), DVec2::new(0.25, 0.));
+	}
+
+	#[test]
+	fn pin_handles_to_viewport() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::new(-50., 50.), DVec2::new(100., 150.)],
+			viewport_clip: Some([DVec2::ZERO, DVec2::new(200., 200.)]),
+			..Default::default()
+		};
+		let mut overlays = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlays);
+		assert_eq!(overlays.squares.len(), 5);
+		assert!(overlays.diamonds.is_empty());
+
+		// The handles on the left edge are drawn at the edge of the viewport, while still being hit at their real positions
+		bounding_box.pin_handles_to_viewport = true;
+		let mut overlays = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlays);
+		assert_eq!(overlays.squares.len(), 5);
+		assert_eq!(overlays.diamonds, [DVec2::new(3., 50.), DVec2::new(3., 100.), DVec2::new(3., 150.)]);
+		assert_eq!(bounding_box.check_selected_edges(DVec2::new(3., 100.)), None);
+		assert!(bounding_box.check_selected_edges(DVec2::new(-50., 100.)).is_some());
+	}
+
+	#[test]
+	fn compute_rotation_snapping() {
+		let bounding_box = BoundingBoxManager::default();
+		let pivot = DVec2::new(10., 10.);
+		let before = pivot + DVec2::X * 50.;
+		let after = pivot + DVec2::from_angle(37.4_f64.to_radians()) * 80.;
+		let rotation = |snap: RotationSnap| bounding_box.compute_rotation(pivot, before, after, snap).to_degrees();
+
+		assert!((rotation(RotationSnap::default()) - 37.4).abs() < 1e-10);
+		let increment = RotationSnap {
+			increment: Some(ROTATE_SNAP_ANGLE.to_radians()),
+			..Default::default()
+		};
+		assert!((rotation(increment) - 30.).abs() < 1e-10);
+		let whole_degrees = RotationSnap {
+			whole_degrees: true,
+			..Default::default()
+		};
+		assert!((rotation(whole_degrees) - 37.).abs() < 1e-10);
+
+		// Candidates win within their tolerance, and otherwise the other snapping applies
+		let candidates = |tolerance: f64| RotationSnap {
+			increment: Some(ROTATE_SNAP_ANGLE.to_radians()),
+			candidates: vec![41_f64.to_radians(), 90_f64.to_radians()],
+			candidate_tolerance: tolerance.to_radians(),
+			..Default::default()
+		};
+		assert!((rotation(candidates(5.)) - 41.).abs() < 1e-10);
+		assert!((rotation(candidates(2.)) - 30.).abs() < 1e-10);
+
+		// No rotation from points on the pivot
+		assert_eq!(bounding_box.compute_rotation(pivot, pivot, after, RotationSnap::default()), 0.);
+	}
+
+	#[test]
+	fn equal_spacing_snap() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut edges = SelectedEdges::new(false, false, false, true, bounds);
+		// A neighbor 20 to the left and one starting at 150 on the right, and one out of the row which is ignored
+		edges.set_spacing_neighbors(vec![
+			[DVec2::new(-60., 0.), DVec2::new(-20., 50.)],
+			[DVec2::new(150., 10.), DVec2::new(200., 40.)],
+			[DVec2::new(135., 100.), DVec2::new(160., 120.)],
+		]);
+
+		let mouse = edges.stick_to_guides(DVec2::new(127., 25.), DAffine2::IDENTITY);
+		assert_eq!(mouse, DVec2::new(130., 25.));
+		assert_eq!(edges.equal_spacing_gaps(), [Some([[-20., 0.], [130., 150.]]), None]);
+		let (_, size) = edges.new_size(mouse, DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, DVec2::new(130., 50.));
+
+		let bounding_box = BoundingBoxManager {
+			bounds,
+			selected_edges: Some(edges.clone()),
+			..Default::default()
+		};
+		assert_eq!(
+			bounding_box.equal_spacing_lines(),
+			[[DVec2::new(-20., 25.), DVec2::new(0., 25.)], [DVec2::new(130., 25.), DVec2::new(150., 25.)]]
+		);
+
+		// Away from the equal gap, nothing sticks
+		let mouse = edges.stick_to_guides(DVec2::new(115., 25.), DAffine2::IDENTITY);
+		assert_eq!(mouse, DVec2::new(115., 25.));
+		assert_eq!(edges.equal_spacing_gaps(), [None, None]);
+
+		// Dragging the left edge mirrors the search, matching the gap between the two neighbors on the left
+		let mut edges = SelectedEdges::new(false, false, true, false, bounds);
+		edges.set_spacing_neighbors(vec![[DVec2::new(-50., 0.), DVec2::new(-40., 50.)], [DVec2::new(-100., 0.), DVec2::new(-70., 50.)]]);
+		let mouse = edges.stick_to_guides(DVec2::new(-23., 25.), DAffine2::IDENTITY);
+		assert_eq!(mouse, DVec2::new(-20., 25.));
+		assert_eq!(edges.equal_spacing_gaps(), [Some([[-40., -20.], [-70., -50.]]), None]);
+	}
+
+	#[test]
+	fn cursor_for_each_region() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			..Default::default()
+		};
+		let cases = [
+			(DVec2::new(0., 0.), MouseCursorIcon::NWSEResize),
+			(DVec2::new(100., 50.), MouseCursorIcon::NWSEResize),
+			(DVec2::new(100., 0.), MouseCursorIcon::NESWResize),
+			(DVec2::new(0., 50.), MouseCursorIcon::NESWResize),
+			(DVec2::new(50., 0.), MouseCursorIcon::NSResize),
+			(DVec2::new(50., 50.), MouseCursorIcon::NSResize),
+			(DVec2::new(0., 25.), MouseCursorIcon::EWResize),
+			(DVec2::new(100., 25.), MouseCursorIcon::EWResize),
+			(DVec2::new(115., 25.), MouseCursorIcon::Rotate),
+			(DVec2::new(50., 25.), MouseCursorIcon::Default),
+			(DVec2::new(300., 300.), MouseCursorIcon::Default),
+		];
+		for (position, expected) in cases {
+			assert_eq!(bounding_box.cursor_for_position(position, true), expected, "At {position}");
+		}
+		assert_eq!(bounding_box.cursor_for_position(DVec2::new(115., 25.), false), MouseCursorIcon::Default);
+
+		// The input handler wrapper gives the same result
+		let mut input = InputPreprocessorMessageHandler::default();
+		input.mouse.position = DVec2::new(115., 25.);
+		assert_eq!(bounding_box.get_cursor(&input, true), MouseCursorIcon::Rotate);
+	}
+
+	#[test]
+	fn dimension_formatting() {
+		let pixels = DimensionFormat::default();
+		assert_eq!(format_dimension(1023.6, &pixels), "1024px");
+		assert_eq!(format_dimension(-0.2, &pixels), "0px");
+
+		let grouped = DimensionFormat {
+			thousands_separator: Some(','),
+			..Default::default()
+		};
+		assert_eq!(format_dimension(1234567.4, &grouped), "1,234,567px");
+		assert_eq!(format_dimension(-4096., &grouped), "-4,096px");
+		assert_eq!(format_dimension(512., &grouped), "512px");
+
+		let millimeters = DimensionFormat {
+			decimals: 2,
+			unit: " mm".to_string(),
+			thousands_separator: None,
+		};
+		assert_eq!(format_dimension(12.5, &millimeters), "12.50 mm");
+		assert_eq!(format_dimension(-3.14159, &millimeters), "-3.14 mm");
+
+		// Shown while resizing, following the scale applied since the start of the drag
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut bounding_box = BoundingBoxManager {
+			bounds,
+			transform: DAffine2::from_scale(DVec2::new(2., 1.)),
+			dimension_readout: Some(millimeters),
+			selected_edges: Some(SelectedEdges::new(false, false, false, true, bounds)),
+			..Default::default()
+		};
+		assert_eq!(bounding_box.readout_dimensions(ReadoutAxes::Local), DVec2::new(200., 50.));
+		let mut overlays = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlays);
+		assert_eq!(overlays.texts, ["200.00 mm × 50.00 mm"]);
+	}
+
+	#[test]
+	fn reflect_across_line() {
+		let bounding_box = BoundingBoxManager::default();
+
+		// Across the 45° line through (10, 0), the point (10, 5) lands at (15, 0)
+		let reflection = bounding_box.reflect_across(DVec2::new(10., 0.), DVec2::new(3., 3.));
+		assert!(reflection.transform_point2(DVec2::new(10., 5.)).abs_diff_eq(DVec2::new(15., 0.), 1e-10));
+		assert!(
+			reflection.transform_point2(DVec2::new(12., 2.)).abs_diff_eq(DVec2::new(12., 2.), 1e-10),
+			"Points on the line stay in place"
+		);
+		assert!((reflection * reflection).abs_diff_eq(DAffine2::IDENTITY, 1e-10));
+		assert!(reflection.matrix2.determinant() < 0.);
+
+		assert_eq!(bounding_box.reflect_across(DVec2::new(10., 0.), DVec2::ZERO), DAffine2::IDENTITY);
+	}
+
+	#[test]
+	fn unconstrained_size_alongside_constrained() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let edges = SelectedEdges::new(false, true, false, true, bounds);
+
+		let (position, size, unconstrained_size) = edges.new_size_with_unconstrained(DVec2::new(160., 60.), DAffine2::IDENTITY, None, true, None);
+		assert_eq!(position, DVec2::ZERO);
+		assert_eq!(unconstrained_size, DVec2::new(160., 60.));
+		assert_ne!(size, unconstrained_size);
+		assert_eq!(size, DVec2::new(160., 80.));
+		assert_eq!(size.x / size.y, 2.);
+		assert_eq!(edges.new_size(DVec2::new(160., 60.), DAffine2::IDENTITY, None, true, None), (position, size));
+
+		// Without a constraint, both are the same
+		let (_, size, unconstrained_size) = edges.new_size_with_unconstrained(DVec2::new(160., 60.), DAffine2::IDENTITY, None, false, None);
+		assert_eq!(size, unconstrained_size);
+	}
+
+	#[test]
+	fn level_two_points() {
+		let bounding_box = BoundingBoxManager::default();
+		let pivot = DVec2::new(30., 40.);
+		let a = DVec2::new(10., 10.);
+		let b = a + DVec2::from_angle(5_f64.to_radians()) * 100.;
+
+		for (start, end) in [(a, b), (b, a)] {
+			let correction = bounding_box.level_points(start, end, pivot);
+			let (_, angle, _) = correction.to_scale_angle_translation();
+			assert!((angle.to_degrees() + 5.).abs() < 1e-10, "Expected a -5° correction but got {}°", angle.to_degrees());
+			assert!(correction.transform_point2(pivot).abs_diff_eq(pivot, 1e-10));
+			let levelled = correction.transform_point2(end) - correction.transform_point2(start);
+			assert!(levelled.y.abs() < 1e-10);
+		}
+
+		assert_eq!(bounding_box.level_points(a, a, pivot), DAffine2::IDENTITY);
+	}
+
+	#[test]
+	fn snapshot_and_restore() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut bounding_box = BoundingBoxManager {
+			bounds,
+			transform: DAffine2::from_translation(DVec2::new(10., 20.)),
+			selected_edges: Some(SelectedEdges::new(false, false, false, true, bounds)),
+			center_of_transformation: DVec2::new(60., 45.),
+			..Default::default()
+		};
+		bounding_box.original_bound_transform = bounding_box.transform;
+		let before = bounding_box.clone();
+		let snapshot = bounding_box.snapshot();
+
+		bounding_box.bounds = [DVec2::ONE, DVec2::splat(2.)];
+		bounding_box.transform = DAffine2::from_angle(1.);
+		bounding_box.original_bound_transform = DAffine2::IDENTITY;
+		bounding_box.selected_edges = None;
+		bounding_box.opposite_pivot = DVec2::splat(7.);
+		bounding_box.center_of_transformation = DVec2::ZERO;
+		assert_ne!(bounding_box, before);
+
+		bounding_box.restore(snapshot.clone());
+		assert_eq!(bounding_box, before);
+		assert_eq!(bounding_box.snapshot(), snapshot);
+	}
+
+	#[test]
+	fn rotation_arc_between_orientations() {
+		let bounds = [DVec2::ZERO, DVec2::new(200., 100.)];
+		let center = DVec2::new(100., 50.);
+		let angle = 30_f64.to_radians();
+		let pivot = DAffine2::from_translation(center);
+		let mut bounding_box = BoundingBoxManager {
+			bounds,
+			transform: pivot * DAffine2::from_angle(angle) * pivot.inverse(),
+			center_of_transformation: center,
+			applied_rotation: Some(angle),
+			rotating: true,
+			..Default::default()
+		};
+		assert_eq!(bounding_box.rotation_arc(), None, "The arc is opt-in");
+
+		bounding_box.show_rotation_arc = true;
+		let (points, label) = bounding_box.rotation_arc().unwrap();
+		assert!(points[0].abs_diff_eq(center + DVec2::X * BOUNDS_ROTATION_ARC_RADIUS, 1e-10));
+		assert!(points.last().unwrap().abs_diff_eq(center + DVec2::from_angle(angle) * BOUNDS_ROTATION_ARC_RADIUS, 1e-10));
+		assert!(points.iter().all(|point| (point.distance(center) - BOUNDS_ROTATION_ARC_RADIUS).abs() < 1e-10));
+
+		// The middle of the arc is over the cage, so the label moves out to its outline along the same direction
+		let [_, max] = bounding_box.aabb();
+		assert!((label.x - max.x).abs() < 1e-10);
+		assert!((label - center).normalize().abs_diff_eq(DVec2::from_angle(angle / 2.), 1e-10));
+		assert!(label.distance(center) > BOUNDS_ROTATION_ARC_RADIUS);
+
+		let mut overlays = RecordingOverlay::default();
+		bounding_box.render_overlays(&mut overlays);
+		assert_eq!(overlays.texts, ["30.0°"]);
+	}
+
+	#[test]
+	fn thresholds_scale_with_device() {
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(200., 100.)],
+			..Default::default()
+		};
+		assert_eq!(bounding_box.select_threshold(), BOUNDS_SELECT_THRESHOLD);
+		assert_eq!(bounding_box.rotate_threshold(), BOUNDS_ROTATE_THRESHOLD);
+		let edge = DVec2::new(200. + BOUNDS_SELECT_THRESHOLD * 1.5, 50.);
+		let band = DVec2::new(200. + BOUNDS_ROTATE_THRESHOLD * 1.5, 50.);
+		assert!(bounding_box.check_selected_edges(edge).is_none());
+		assert!(!bounding_box.check_rotate(band));
+
+		bounding_box.device_scale = 2.;
+		assert_eq!(bounding_box.select_threshold(), BOUNDS_SELECT_THRESHOLD * 2.);
+		assert_eq!(bounding_box.rotate_threshold(), BOUNDS_ROTATE_THRESHOLD * 2.);
+		assert!(bounding_box.check_selected_edges(edge).is_some());
+		assert!(bounding_box.check_rotate(band));
+	}
+
+	#[test]
+	fn local_readout_dimensions_ignore_rotation() {
+		let center = DVec2::new(50., 25.);
+		let pivot = DAffine2::from_translation(center);
+		let original = DAffine2::from_scale(DVec2::splat(2.));
+		let mut bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			original_bound_transform: original,
+			..Default::default()
+		};
+
+		for degrees in [0., 10., 45., 90., 170.] {
+			bounding_box.transform = pivot * DAffine2::from_angle(f64::to_radians(degrees)) * pivot.inverse() * original;
+			let local = bounding_box.readout_dimensions(ReadoutAxes::Local);
+			assert!(local.abs_diff_eq(DVec2::new(100., 50.), 1e-10), "At {degrees}° the local size is {local}");
+		}
+
+		// The axis-aligned size changes with the rotation, here to that of the box around the quarter turned cage
+		let aligned = bounding_box.readout_dimensions(ReadoutAxes::Aligned);
+		bounding_box.transform = pivot * DAffine2::from_angle(FRAC_PI_2) * pivot.inverse() * original;
+		assert!(bounding_box.readout_dimensions(ReadoutAxes::Aligned).abs_diff_eq(DVec2::new(50., 100.), 1e-10));
+		assert!(!aligned.abs_diff_eq(DVec2::new(100., 50.), 1e-6));
+	}
+
+	#[test]
+	fn handle_proximity_falls_off_to_threshold() {
+		let bounding_box = BoundingBoxManager {
+			bounds: [DVec2::ZERO, DVec2::new(100., 50.)],
+			transform: DAffine2::from_angle(0.3),
+			..Default::default()
+		};
+		let handles = bounding_box.evaluate_transform_handle_positions();
+
+		assert_eq!(bounding_box.handle_proximity(handles[7]), Some((7, 1.)));
+		let (index, proximity) = bounding_box.handle_proximity(handles[3] + DVec2::new(0., BOUNDS_SELECT_THRESHOLD * 0.9)).unwrap();
+		assert_eq!(index, 3);
+		assert!((proximity - 0.1).abs() < 1e-10);
+		assert_eq!(bounding_box.handle_proximity(handles[3] + DVec2::new(0., BOUNDS_SELECT_THRESHOLD * 1.1)), None);
+	}
+
+	#[test]
+	fn lock_to_initial_drag_direction() {
+		let bounds = [DVec2::ZERO, DVec2::new(100., 50.)];
+		let mut bounding_box = BoundingBoxManager { bounds, ..Default::default() };
+		let edges = SelectedEdges::new(false, true, false, true, bounds);
+		bounding_box.begin_drag(DVec2::new(100., 50.));
+
+		// An L-shaped path, first right and then down, where only the rightwards part applies
+		let mut size = DVec2::ZERO;
+		for mouse in [DVec2::new(100.5, 50.), DVec2::new(110., 50.), DVec2::new(130., 50.), DVec2::new(130., 70.), DVec2::new(130., 90.)] {
+			let mouse = bounding_box.lock_drag_direction(mouse, true);
+			(_, size) = edges.new_size(mouse, DAffine2::IDENTITY, None, false, None);
+		}
+		assert_eq!(bounding_box.drag_direction, Some(DVec2::X));
+		assert_eq!(size, DVec2::new(130., 50.));
+
+		// Without the lock, the whole path applies
+		assert_eq!(bounding_box.lock_drag_direction(DVec2::new(130., 90.), false), DVec2::new(130., 90.));
+
+		bounding_box.end_rotation();
+		assert_eq!(bounding_box.drag_direction, None);
+	}
+
+	#[test]
+	fn local_to_screen_maps_unit_square() {
+		let bounds = [DVec2::new(20., 10.), DVec2::new(120., 60.)];
+		let transform = DAffine2::from_translation(DVec2::new(5., -5.)) * DAffine2::from_angle(0.3) * DAffine2::from_scale(DVec2::splat(2.));
+		let bounding_box = BoundingBoxManager {
+			bounds,
+			transform,
+			..Default::default()
+		};
+		let local_to_screen = bounding_box.local_to_screen();
+
+		let center = bounding_box.to_screen((bounds[0] + bounds[1]) / 2.);
+		assert!(local_to_screen.transform_point2(DVec2::splat(0.5)).abs_diff_eq(center, 1e-10));
+		assert!(local_to_screen.transform_point2(DVec2::ZERO).abs_diff_eq(bounding_box.to_screen(bounds[0]), 1e-10));
+		assert!(local_to_screen.transform_point2(DVec2::ONE).abs_diff_eq(bounding_box.to_screen(bounds[1]), 1e-10));
+	}
 }
@@ -123,20 +123,21 @@ impl ArtboardToolData {
 		}
 	}
 
-	fn check_dragging_bounds(&mut self, cursor: DVec2) -> Option<(bool, bool, bool, bool)> {
+	fn check_dragging_bounds(&mut self, cursor: DVec2) -> Option<EdgeSelection> {
 		let bounding_box = self.bounding_box_manager.as_mut()?;
 		let edges = bounding_box.check_selected_edges(cursor)?;
-		let (top, bottom, left, right) = edges;
-		let selected_edges = SelectedEdges::new(top, bottom, left, right, bounding_box.bounds);
+		let selected_edges = SelectedEdges::from_edge_selection(edges, bounding_box.bounds);
 		bounding_box.opposite_pivot = selected_edges.calculate_pivot();
 		bounding_box.selected_edges = Some(selected_edges);
 
 		Some(edges)
 	}
 
-	fn start_resizing(&mut self, _selected_edges: (bool, bool, bool, bool), _document: &DocumentMessageHandler, _input: &InputPreprocessorMessageHandler) {
+	fn start_resizing(&mut self, _selected_edges: EdgeSelection, _document: &DocumentMessageHandler, _input: &InputPreprocessorMessageHandler) {
 		if let Some(bounds) = &mut self.bounding_box_manager {
-			bounds.center_of_transformation = bounds.transform.transform_point2((bounds.bounds[0] + bounds.bounds[1]) / 2.);
+			if let Some(movement) = &bounds.selected_edges {
+				bounds.center_of_transformation = bounds.to_screen(movement.default_center_around());
+			}
 		}
 	}
 
@@ -178,7 +179,7 @@ impl ArtboardToolData {
 			return;
 		}
 
-		let center = from_center.then_some(bounds.center_of_transformation);
+		let center = from_center.then(|| bounds.centroid.unwrap_or(bounds.center_of_transformation));
 		let ignore = self.selected_artboard.map_or(Vec::new(), |layer| vec![layer]);
 		let snap = Some(SizeSnapData {
 			manager: &mut self.snap_manager,
@@ -232,6 +233,7 @@ impl Fsm for ArtboardToolFsmState {
 						bounding_box_manager.bounds = bounds;
 						bounding_box_manager.transform = document.metadata().document_to_viewport;
 
+						bounding_box_manager.viewport_clip = Some([DVec2::ZERO, overlay_context.size]);
 						bounding_box_manager.render_overlays(&mut overlay_context);
 					} else {
 						tool_data.bounding_box_manager.take();
@@ -444,6 +446,7 @@ impl Fsm for ArtboardToolFsmState {
 
 				if let Some(bounds) = &mut tool_data.bounding_box_manager {
 					bounds.original_transforms.clear();
+					bounds.selected_edges = None;
 				}
 
 				responses.add(OverlaysMessage::Draw);
@@ -1,14 +1,14 @@
 #![allow(clippy::too_many_arguments)]
 
 use super::tool_prelude::*;
-use crate::consts::{ROTATE_SNAP_ANGLE, SELECTION_TOLERANCE};
+use crate::consts::SELECTION_TOLERANCE;
 use crate::messages::input_mapper::utility_types::input_mouse::ViewportPosition;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis};
 use crate::messages::portfolio::document::utility_types::network_interface::{FlowType, NodeNetworkInterface, NodeTemplate};
-use crate::messages::portfolio::document::utility_types::transformation::Selected;
+use crate::messages::portfolio::document::utility_types::transformation::{Selected, Typing};
 use crate::messages::tool::common_functionality::graph_modification_utils::is_layer_fed_by_node_of_name;
 use crate::messages::tool::common_functionality::pivot::Pivot;
 use crate::messages::tool::common_functionality::snapping::{self, SnapCandidatePoint, SnapData, SnapManager};
@@ -79,6 +79,10 @@ pub enum SelectToolMessage {
 	PointerOutsideViewport(SelectToolPointerKeys),
 	SelectOptions(SelectOptionsUpdate),
 	SetPivot { position: PivotPosition },
+	SetPivotAnchor { digit: u8 },
+	TypeBackspace,
+	TypeDecimalPoint,
+	TypeDigit { digit: u8 },
 }
 
 impl ToolMetadata for SelectTool {
@@ -229,6 +233,7 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionHandlerData<'a>> for SelectT
 
 		let additional = match self.fsm_state {
 			SelectToolFsmState::Ready { .. } => actions!(SelectToolMessageDiscriminant; DragStart),
+			SelectToolFsmState::ResizingBounds => actions!(SelectToolMessageDiscriminant; DragStop, SetPivotAnchor, TypeBackspace, TypeDecimalPoint, TypeDigit),
 			_ => actions!(SelectToolMessageDiscriminant; DragStop),
 		};
 		common.extend(additional);
@@ -280,6 +285,7 @@ struct SelectToolData {
 	selected_layers_changed: bool,
 	snap_candidates: Vec<SnapCandidatePoint>,
 	auto_panning: AutoPanning,
+	typing: Typing,
 }
 
 impl SelectToolData {
@@ -296,6 +302,46 @@ impl SelectToolData {
 		}
 	}
 
+	/// Scales the layers being resized so the bounds take the given position and size, in the local space of the bounds at the start of the drag
+	fn resize_bounds(&mut self, position: DVec2, size: DVec2, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		let Some(bounds) = &mut self.bounding_box_manager else { return };
+		let Some(movement) = &bounds.selected_edges else { return };
+		let (delta, mut pivot) = movement.bounds_to_scale_transform(position, size);
+		bounds.locked_ratio = movement.locked_ratio(size);
+
+		let pivot_transform = DAffine2::from_translation(pivot);
+		let transformation = pivot_transform * delta * pivot_transform.inverse();
+
+		self.layers_dragging.retain(|layer| {
+			if *layer != LayerNodeIdentifier::ROOT_PARENT {
+				document.network_interface.network(&[]).unwrap().nodes.contains_key(&layer.to_node())
+			} else {
+				log::error!("ROOT_PARENT should not be part of layers_dragging");
+				false
+			}
+		});
+		let mut selected = Selected::new(
+			&mut bounds.original_transforms,
+			&mut pivot,
+			&self.layers_dragging,
+			responses,
+			&document.network_interface,
+			None,
+			&ToolType::Select,
+		);
+
+		selected.apply_transformation(bounds.original_bound_transform * transformation * bounds.original_bound_transform.inverse());
+	}
+
+	/// Replaces the dragged dimension of the resize in progress with a typed value, or hands it back to the cursor on its next move when the typed value is cleared
+	fn type_dimension(&mut self, value: Option<f64>, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		let Some(bounds) = &mut self.bounding_box_manager else { return };
+		bounds.numeric_override = value;
+		if let Some((position, size)) = bounds.numeric_override_size() {
+			self.resize_bounds(position, size, document, responses);
+		}
+	}
+
 	fn selection_quad(&self) -> Quad {
 		let bbox = self.selection_box();
 		Quad::from_box(bbox)
@@ -456,6 +502,7 @@ impl Fsm for SelectToolFsmState {
 					bounding_box_manager.bounds = bounds;
 					bounding_box_manager.transform = transform;
 
+					bounding_box_manager.viewport_clip = Some([DVec2::ZERO, overlay_context.size]);
 					bounding_box_manager.render_overlays(&mut overlay_context);
 				} else {
 					tool_data.bounding_box_manager.take();
@@ -524,8 +571,8 @@ impl Fsm for SelectToolFsmState {
 				let dragging_bounds = tool_data.bounding_box_manager.as_mut().and_then(|bounding_box| {
 					let edges = bounding_box.check_selected_edges(input.mouse.position);
 
-					bounding_box.selected_edges = edges.map(|(top, bottom, left, right)| {
-						let selected_edges = SelectedEdges::new(top, bottom, left, right, bounding_box.bounds);
+					bounding_box.selected_edges = edges.map(|edges| {
+						let selected_edges = SelectedEdges::from_edge_selection(edges, bounding_box.bounds);
 						bounding_box.opposite_pivot = selected_edges.calculate_pivot();
 						selected_edges
 					});
@@ -572,6 +619,8 @@ impl Fsm for SelectToolFsmState {
 
 					if let Some(bounds) = &mut tool_data.bounding_box_manager {
 						bounds.original_bound_transform = bounds.transform;
+						bounds.clear_angle_guide();
+						bounds.begin_drag(input.mouse.position);
 
 						tool_data.layers_dragging.retain(|layer| {
 							if *layer != LayerNodeIdentifier::ROOT_PARENT {
@@ -602,6 +651,9 @@ impl Fsm for SelectToolFsmState {
 					responses.add(DocumentMessage::StartTransaction);
 
 					if let Some(bounds) = &mut tool_data.bounding_box_manager {
+						bounds.original_bound_transform = bounds.transform;
+						bounds.clear_angle_guide();
+
 						tool_data.layers_dragging.retain(|layer| {
 							if *layer != LayerNodeIdentifier::ROOT_PARENT {
 								document.network_interface.network(&[]).unwrap().nodes.contains_key(&layer.to_node())
@@ -621,6 +673,7 @@ impl Fsm for SelectToolFsmState {
 						);
 
 						bounds.center_of_transformation = selected.mean_average_of_pivots();
+						bounds.begin_rotation(input.mouse.position);
 					}
 
 					tool_data.layers_dragging = selected;
@@ -638,6 +691,9 @@ impl Fsm for SelectToolFsmState {
 					}
 
 					tool_data.layers_dragging = selected;
+					if let Some(bounds) = &mut tool_data.bounding_box_manager {
+						bounds.clear_angle_guide();
+					}
 
 					tool_data.get_snap_candidates(document, input);
 
@@ -703,12 +759,18 @@ impl Fsm for SelectToolFsmState {
 				let snap_data = SnapData::ignore(document, input, ignore);
 				let (start, current) = (tool_data.drag_start, tool_data.drag_current);
 				let mouse_delta = snap_drag(start, current, axis_align, snap_data, &mut tool_data.snap_manager, &tool_data.snap_candidates);
+				let mut translation = DAffine2::from_translation(mouse_delta);
+				if let Some(bounds) = &mut tool_data.bounding_box_manager {
+					bounds.update_axis_guide(axis_align, start, input.mouse.position);
+					// The snapped movement is already axis aligned by `snap_drag`, so it isn't aligned again
+					translation = bounds.translate(mouse_delta, false);
+				}
 
 				// TODO: Cache the result of `shallowest_unique_layers` to avoid this heavy computation every frame of movement, see https://github.com/GraphiteEditor/Graphite/pull/481
 				for layer in document.network_interface.shallowest_unique_layers(&[]) {
 					responses.add_front(GraphOperationMessage::TransformChange {
 						layer,
-						transform: DAffine2::from_translation(mouse_delta),
+						transform: translation,
 						transform_in: TransformIn::Viewport,
 						skip_rerender: false,
 					});
@@ -725,62 +787,49 @@ impl Fsm for SelectToolFsmState {
 				SelectToolFsmState::Dragging
 			}
 			(SelectToolFsmState::ResizingBounds, SelectToolMessage::PointerMove(modifier_keys)) => {
+				let mut resize = None;
 				if let Some(ref mut bounds) = &mut tool_data.bounding_box_manager {
+					let mouse = bounds.steady_cursor(input.mouse.position);
+					let typed = bounds.numeric_override_size();
 					if let Some(movement) = &mut bounds.selected_edges {
 						let (center, constrain) = (input.keyboard.key(modifier_keys.center), input.keyboard.key(modifier_keys.axis_align));
 
-						let center = center.then_some(bounds.center_of_transformation);
+						let center = center.then(|| bounds.centroid.unwrap_or(bounds.center_of_transformation));
 						let snap = Some(SizeSnapData {
 							manager: &mut tool_data.snap_manager,
 							points: &mut tool_data.snap_candidates,
 							snap_data: SnapData::ignore(document, input, &tool_data.layers_dragging),
 						});
-						let (position, size) = movement.new_size(input.mouse.position, bounds.original_bound_transform, center, constrain, snap);
-						let (delta, mut pivot) = movement.bounds_to_scale_transform(position, size);
-
-						let pivot_transform = DAffine2::from_translation(pivot);
-						let transformation = pivot_transform * delta * pivot_transform.inverse();
-
-						tool_data.layers_dragging.retain(|layer| {
-							if *layer != LayerNodeIdentifier::ROOT_PARENT {
-								document.network_interface.network(&[]).unwrap().nodes.contains_key(&layer.to_node())
-							} else {
-								log::error!("ROOT_PARENT should not be part of layers_dragging");
-								false
-							}
+						let mouse = movement.stick_to_guides(mouse, bounds.original_bound_transform);
+						// A typed dimension replaces the one following the cursor until the resize is finalized
+						resize = Some(match typed {
+							Some(typed) => typed,
+							None => movement.new_size(mouse, bounds.original_bound_transform, center, constrain, snap),
 						});
-						let selected = &tool_data.layers_dragging;
-						let mut selected = Selected::new(&mut bounds.original_transforms, &mut pivot, selected, responses, &document.network_interface, None, &ToolType::Select);
-
-						selected.apply_transformation(bounds.original_bound_transform * transformation * bounds.original_bound_transform.inverse());
-
-						// AutoPanning
-						let messages = [
-							SelectToolMessage::PointerOutsideViewport(modifier_keys.clone()).into(),
-							SelectToolMessage::PointerMove(modifier_keys).into(),
-						];
-						tool_data.auto_panning.setup_by_mouse_position(input, &messages, responses);
+						bounds.constrain_active = constrain;
 					}
 				}
+				if let Some((position, size)) = resize {
+					tool_data.resize_bounds(position, size, document, responses);
+
+					// AutoPanning
+					let messages = [
+						SelectToolMessage::PointerOutsideViewport(modifier_keys.clone()).into(),
+						SelectToolMessage::PointerMove(modifier_keys).into(),
+					];
+					tool_data.auto_panning.setup_by_mouse_position(input, &messages, responses);
+				}
 				SelectToolFsmState::ResizingBounds
 			}
 			(SelectToolFsmState::RotatingBounds, SelectToolMessage::PointerMove(modifier_keys)) => {
 				if let Some(bounds) = &mut tool_data.bounding_box_manager {
-					let angle = {
-						let start_offset = tool_data.drag_start - bounds.center_of_transformation;
-						let end_offset = input.mouse.position - bounds.center_of_transformation;
-
-						start_offset.angle_to(end_offset)
-					};
-
-					let snapped_angle = if input.keyboard.key(modifier_keys.snap_angle) {
-						let snap_resolution = ROTATE_SNAP_ANGLE.to_radians();
-						(angle / snap_resolution).round() * snap_resolution
-					} else {
-						angle
-					};
+					let snap_angle = input.keyboard.key(modifier_keys.snap_angle);
+					let mouse = bounds.steady_cursor(input.mouse.position);
+					let angle = bounds.rotation_delta(mouse, snap_angle);
 
-					let delta = DAffine2::from_angle(snapped_angle);
+					let delta = DAffine2::from_angle(angle);
+					bounds.applied_rotation = Some(angle);
+					bounds.update_angle_guide(snap_angle);
 
 					tool_data.layers_dragging.retain(|layer| {
 						if *layer != LayerNodeIdentifier::ROOT_PARENT {
@@ -807,7 +856,12 @@ impl Fsm for SelectToolFsmState {
 			}
 			(SelectToolFsmState::DraggingPivot, SelectToolMessage::PointerMove(modifier_keys)) => {
 				let mouse_position = input.mouse.position;
-				let snapped_mouse_position = mouse_position;
+				let snapping_enabled = document.snapping_state.snapping_enabled;
+				let snapped_mouse_position = tool_data.bounding_box_manager.as_mut().map_or(mouse_position, |bounds| {
+					// Documents don't have guides yet, so the handles and the center of the bounds are the only snap targets
+					let pivot = if snapping_enabled { bounds.snap_pivot(mouse_position, &[], &[]) } else { mouse_position };
+					bounds.set_pivot(pivot)
+				});
 				tool_data.pivot.set_viewport_position(snapped_mouse_position, document, responses);
 
 				// AutoPanning
@@ -834,7 +888,7 @@ impl Fsm for SelectToolFsmState {
 				SelectToolFsmState::DrawingBox { selection }
 			}
 			(SelectToolFsmState::Ready { .. }, SelectToolMessage::PointerMove(_)) => {
-				let mut cursor = tool_data.bounding_box_manager.as_ref().map_or(MouseCursorIcon::Default, |bounds| bounds.get_cursor(input, true));
+				let mut cursor = tool_data.bounding_box_manager.as_mut().map_or(MouseCursorIcon::Default, |bounds| bounds.update_cursor(input, true));
 
 				// Dragging the pivot overrules the other operations
 				if tool_data.pivot.is_over(input.mouse.position) {
@@ -903,6 +957,9 @@ impl Fsm for SelectToolFsmState {
 				};
 				tool_data.snap_manager.cleanup(responses);
 				responses.add_front(response);
+				if let Some(bounds) = &mut tool_data.bounding_box_manager {
+					bounds.clear_axis_guide();
+				}
 
 				let selection = tool_data.nested_selection_behavior;
 				SelectToolFsmState::Ready { selection }
@@ -955,6 +1012,9 @@ impl Fsm for SelectToolFsmState {
 
 				tool_data.has_dragged = false;
 				tool_data.layer_selected_on_start = None;
+				if let Some(bounds) = &mut tool_data.bounding_box_manager {
+					bounds.clear_axis_guide();
+				}
 
 				tool_data.snap_manager.cleanup(responses);
 				tool_data.select_single_layer = None;
@@ -963,7 +1023,8 @@ impl Fsm for SelectToolFsmState {
 				SelectToolFsmState::Ready { selection }
 			}
 			(SelectToolFsmState::ResizingBounds, SelectToolMessage::DragStop { .. } | SelectToolMessage::Enter) => {
-				let response = match input.mouse.position.distance(tool_data.drag_start) < 10. * f64::EPSILON {
+				let typed = tool_data.bounding_box_manager.as_ref().is_some_and(|bounds| bounds.numeric_override.is_some());
+				let response = match input.mouse.position.distance(tool_data.drag_start) < 10. * f64::EPSILON && !typed {
 					true => DocumentMessage::AbortTransaction,
 					false => DocumentMessage::EndTransaction,
 				};
@@ -973,7 +1034,16 @@ impl Fsm for SelectToolFsmState {
 
 				if let Some(bounds) = &mut tool_data.bounding_box_manager {
 					bounds.original_transforms.clear();
+					bounds.selected_edges = None;
+					bounds.locked_ratio = None;
+					bounds.constrain_active = false;
+					bounds.steadied_cursor = None;
+					bounds.press_position = None;
+					bounds.drag_direction = None;
+					bounds.numeric_override = None;
+					bounds.checkpoints.clear();
 				}
+				tool_data.typing.clear();
 
 				let selection = tool_data.nested_selection_behavior;
 				SelectToolFsmState::Ready { selection }
@@ -987,6 +1057,7 @@ impl Fsm for SelectToolFsmState {
 
 				if let Some(bounds) = &mut tool_data.bounding_box_manager {
 					bounds.original_transforms.clear();
+					bounds.end_rotation();
 				}
 
 				let selection = tool_data.nested_selection_behavior;
@@ -1048,6 +1119,22 @@ impl Fsm for SelectToolFsmState {
 			(SelectToolFsmState::Dragging, SelectToolMessage::Abort) => {
 				responses.add(DocumentMessage::AbortTransaction);
 				tool_data.snap_manager.cleanup(responses);
+				if let Some(bounds) = &mut tool_data.bounding_box_manager {
+					bounds.clear_axis_guide();
+				}
+				responses.add(OverlaysMessage::Draw);
+
+				let selection = tool_data.nested_selection_behavior;
+				SelectToolFsmState::Ready { selection }
+			}
+			(SelectToolFsmState::ResizingBounds | SelectToolFsmState::RotatingBounds, SelectToolMessage::Abort) => {
+				responses.add(DocumentMessage::AbortTransaction);
+				// Aborting the transaction already restores the layers, so the original transforms aren't needed
+				if let Some(bounds) = &mut tool_data.bounding_box_manager {
+					bounds.cancel();
+				}
+				tool_data.typing.clear();
+				tool_data.snap_manager.cleanup(responses);
 				responses.add(OverlaysMessage::Draw);
 
 				let selection = tool_data.nested_selection_behavior;
@@ -1062,6 +1149,12 @@ impl Fsm for SelectToolFsmState {
 					}
 				});
 
+				if let Some(bounds) = &mut tool_data.bounding_box_manager {
+					bounds.end_rotation();
+					bounds.locked_ratio = None;
+					bounds.clear_axis_guide();
+				}
+
 				responses.add(OverlaysMessage::Draw);
 
 				tool_data.snap_manager.cleanup(responses);
@@ -1077,6 +1170,35 @@ impl Fsm for SelectToolFsmState {
 
 				self
 			}
+			(SelectToolFsmState::ResizingBounds, SelectToolMessage::SetPivotAnchor { digit }) => {
+				let Some(position) = numpad_pivot_position(digit) else { return self };
+				let Some(bounds) = &mut tool_data.bounding_box_manager else { return self };
+
+				if let Some(normalized) = Option::<DVec2>::from(position) {
+					bounds.set_pivot_anchor(position);
+					tool_data.pivot.set_normalized_position(normalized, document, responses);
+				}
+
+				self
+			}
+			(SelectToolFsmState::ResizingBounds, SelectToolMessage::TypeBackspace) => {
+				let value = tool_data.typing.type_backspace();
+				tool_data.type_dimension(value, document, responses);
+
+				self
+			}
+			(SelectToolFsmState::ResizingBounds, SelectToolMessage::TypeDecimalPoint) => {
+				let value = tool_data.typing.type_decimal_point();
+				tool_data.type_dimension(value, document, responses);
+
+				self
+			}
+			(SelectToolFsmState::ResizingBounds, SelectToolMessage::TypeDigit { digit }) => {
+				let value = tool_data.typing.type_number(digit);
+				tool_data.type_dimension(value, document, responses);
+
+				self
+			}
 			_ => self,
 		}
 	}
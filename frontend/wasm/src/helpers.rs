@@ -86,16 +86,16 @@ pub fn translate_key(name: &str) -> Key {
 		"ArrowUp" => ArrowUp,
 
 		// Numpad keys
-		// "Numpad0" => KeyNumpad0,
-		// "Numpad1" => KeyNumpad1,
-		// "Numpad2" => KeyNumpad2,
-		// "Numpad3" => KeyNumpad3,
-		// "Numpad4" => KeyNumpad4,
-		// "Numpad5" => KeyNumpad5,
-		// "Numpad6" => KeyNumpad6,
-		// "Numpad7" => KeyNumpad7,
-		// "Numpad8" => KeyNumpad8,
-		// "Numpad9" => KeyNumpad9,
+		"Numpad0" => Numpad0,
+		"Numpad1" => Numpad1,
+		"Numpad2" => Numpad2,
+		"Numpad3" => Numpad3,
+		"Numpad4" => Numpad4,
+		"Numpad5" => Numpad5,
+		"Numpad6" => Numpad6,
+		"Numpad7" => Numpad7,
+		"Numpad8" => Numpad8,
+		"Numpad9" => Numpad9,
 		"NumLock" => NumLock,
 		"NumpadAdd" => NumpadAdd,
 		// "NumpadBackspace" => KeyNumpadBackspace,